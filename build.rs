@@ -1,18 +1,91 @@
 use std::{
-    collections::{hash_map::Entry, HashMap},
-    env, io,
+    collections::{hash_map::Entry, BTreeMap, HashMap, HashSet},
+    env,
+    f64::consts::{PI, TAU},
+    fmt, fs,
+    ops::RangeInclusive,
     path::{Path, PathBuf},
+    process,
 };
 
-use codegen::{Function, Scope, Variant};
-use serde::Deserialize;
+use codegen::Scope;
+use mask::AlphaMask;
+use serde::{
+    de::{
+        self,
+        value::{MapAccessDeserializer, SeqAccessDeserializer},
+        IgnoredAny, MapAccess, SeqAccess, Visitor,
+    },
+    Deserialize, Deserializer,
+};
+
+// Only the pieces needed to embed masks and PNGs are used here.
+#[allow(dead_code)]
+#[path = "src/mask.rs"]
+mod mask;
+#[path = "src/png.rs"]
+mod png;
+#[path = "src/raster.rs"]
+mod raster;
+#[path = "src/suggest.rs"]
+mod suggest;
+#[path = "src/svg.rs"]
+mod svg;
 
 const CONFIG_FILE: &str = "icons.json";
+const CONFIG_ENV: &str = "MATERIAL_ICONS_CONFIG";
+const METADATA_KEY: &str = "material-icons";
 const SHIPPED_ICONS_PATH: &str = "icons";
 
 const CONSTANTS_FILE: &str = "icons.rs";
 
-#[derive(Debug, Default, Deserialize)]
+const DEFAULT_WEIGHT: u16 = 400;
+const DEFAULT_GRADE: i16 = 0;
+const DEFAULT_OPTICAL_SIZE: u8 = 24;
+
+/// Icons renamed between the Material Icons font and Material Symbols, as
+/// `(old, new)`. The old names keep working as deprecated aliases of the new ones.
+const RENAMED_ICONS: &[(&str, &str)] = &[
+    ("access_alarm", "alarm"),
+    ("add_circle_outline", "add_circle"),
+    ("bookmark_border", "bookmark"),
+    ("camera_alt", "photo_camera"),
+    ("chat_bubble_outline", "chat_bubble"),
+    ("check_circle_outline", "check_circle"),
+    ("create", "edit"),
+    ("delete_outline", "delete"),
+    ("done", "check"),
+    ("error_outline", "error"),
+    ("favorite_border", "favorite"),
+    ("file_download", "download"),
+    ("file_upload", "upload"),
+    ("help_outline", "help"),
+    ("highlight_off", "cancel"),
+    ("info_outline", "info"),
+    ("insert_drive_file", "draft"),
+    ("insert_photo", "image"),
+    ("label_outline", "label"),
+    ("lightbulb_outline", "lightbulb"),
+    ("lock_outline", "lock"),
+    ("mail_outline", "mail"),
+    ("mode_edit", "edit"),
+    ("notifications_none", "notifications"),
+    ("perm_identity", "person"),
+    ("report_problem", "warning"),
+    ("star_border", "star"),
+    ("star_outline", "star"),
+    ("warning_amber", "warning"),
+    ("work_outline", "work"),
+];
+
+const WEIGHTS: [u16; 7] = [100, 200, 300, 400, 500, 600, 700];
+const GRADES: [i16; 3] = [-25, 0, 200];
+const OPTICAL_SIZES: [u8; 4] = [20, 24, 40, 48];
+/// Sizes icons can be rasterized at while building, keeping the embedded
+/// masks at a reasonable size.
+const RASTER_SIZES: RangeInclusive<u32> = 1..=1024;
+
+#[derive(Debug, Default, Clone, PartialEq, Eq, PartialOrd, Ord, Deserialize)]
 enum IconStyle {
     #[default]
     #[serde(rename = "outlined")]
@@ -23,13 +96,322 @@ enum IconStyle {
     Sharp,
 }
 
+impl IconStyle {
+    fn as_str(&self) -> &'static str {
+        match self {
+            Self::Outlined => "outlined",
+            Self::Rounded => "rounded",
+            Self::Sharp => "sharp",
+        }
+    }
+}
+
+/// The `style` of a configured icon: a single style, a list of them or `"all"`.
 #[derive(Deserialize)]
-struct IconInfo {
+#[serde(
+    untagged,
+    expecting = r#"expected `style` to be "outlined", "rounded", "sharp", a list of them or "all""#
+)]
+enum Styles {
+    One(IconStyle),
+    All(AllStyles),
+    Many(Vec<IconStyle>),
+}
+
+#[derive(Deserialize)]
+enum AllStyles {
+    #[serde(rename = "all")]
+    All,
+}
+
+impl Default for Styles {
+    fn default() -> Self {
+        Self::One(IconStyle::default())
+    }
+}
+
+impl Styles {
+    fn to_vec(&self) -> Vec<IconStyle> {
+        match self {
+            Self::One(style) => vec![style.clone()],
+            Self::All(AllStyles::All) => {
+                vec![IconStyle::Outlined, IconStyle::Rounded, IconStyle::Sharp]
+            }
+            Self::Many(styles) => styles.clone(),
+        }
+    }
+}
+
+/// The `filled` flag of a configured icon: `true`, `false` or `"both"`.
+#[derive(Deserialize)]
+#[serde(
+    untagged,
+    expecting = r#"expected `filled` to be true, false or "both""#
+)]
+enum Fills {
+    One(bool),
+    Both(BothFills),
+}
+
+#[derive(Deserialize)]
+enum BothFills {
+    #[serde(rename = "both")]
+    Both,
+}
+
+impl Default for Fills {
+    fn default() -> Self {
+        Self::One(false)
+    }
+}
+
+impl Fills {
+    fn to_vec(&self) -> Vec<bool> {
+        match self {
+            Self::One(filled) => vec![*filled],
+            Self::Both(BothFills::Both) => vec![false, true],
+        }
+    }
+}
+
+/// An icon as configured in icons.json, possibly standing for several variants.
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct IconEntry {
     name: String,
     #[serde(default)]
-    style: IconStyle,
+    style: Styles,
+    #[serde(default)]
+    filled: Fills,
+    #[serde(default = "default_weight")]
+    weight: u16,
+    #[serde(default = "default_grade")]
+    grade: i16,
+    #[serde(default = "default_optical_size")]
+    optical_size: u8,
+    /// SVG file to use instead of looking the icon up by name.
+    #[serde(default)]
+    path: Option<PathBuf>,
+    /// Other names the icon should be available under.
     #[serde(default)]
+    alias: Aliases,
+    /// Sizes, in pixels, to rasterize the icon at while building.
+    #[serde(default)]
+    raster_sizes: Vec<u32>,
+    /// Sizes, in pixels, to encode the icon as PNG at while building.
+    #[serde(default)]
+    png_sizes: Vec<u32>,
+    /// The size to draw the SVG document at instead of its own.
+    #[serde(default)]
+    resize: Option<Resize>,
+}
+
+/// Rewrites the size of an SVG document, see `svg::resize`.
+#[derive(Clone, Copy, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Resize {
+    width: f32,
+    height: f32,
+    /// Added around the viewBox on every side, in the units of the viewBox.
+    #[serde(default)]
+    padding: f32,
+}
+
+/// The `alias` of a configured icon: a single name or a list of them.
+#[derive(Default, Deserialize)]
+#[serde(
+    untagged,
+    expecting = "expected `alias` to be a name or a list of names"
+)]
+enum Aliases {
+    #[default]
+    None,
+    One(String),
+    Many(Vec<String>),
+}
+
+impl Aliases {
+    fn to_vec(&self) -> Vec<String> {
+        match self {
+            Self::None => Vec::new(),
+            Self::One(alias) => vec![alias.clone()],
+            Self::Many(aliases) => aliases.clone(),
+        }
+    }
+}
+
+/// A single variant of an icon to generate code for.
+#[derive(Clone)]
+struct IconInfo {
+    name: String,
+    style: IconStyle,
     filled: bool,
+    weight: u16,
+    grade: i16,
+    optical_size: u8,
+    path: Option<PathBuf>,
+    aliases: Vec<String>,
+    raster_sizes: Vec<u32>,
+    png_sizes: Vec<u32>,
+    resize: Option<Resize>,
+}
+
+fn default_weight() -> u16 {
+    DEFAULT_WEIGHT
+}
+
+fn default_grade() -> i16 {
+    DEFAULT_GRADE
+}
+
+fn default_optical_size() -> u8 {
+    DEFAULT_OPTICAL_SIZE
+}
+
+impl IconInfo {
+    /// Checks that the variable font axes are set to values Material Symbols
+    /// are published in.
+    fn validate(&self) -> Result<(), String> {
+        if self.name.is_empty() || self.name.contains(['/', '\\']) || self.name.starts_with('.') {
+            return Err(format!("{:?} isn't a valid icon name", self.name));
+        }
+
+        if !WEIGHTS.contains(&self.weight) {
+            return Err(format!(
+                "Icon {} has weight {}, expected one of {WEIGHTS:?}",
+                self.name, self.weight
+            ));
+        }
+
+        if !GRADES.contains(&self.grade) {
+            return Err(format!(
+                "Icon {} has grade {}, expected one of {GRADES:?}",
+                self.name, self.grade
+            ));
+        }
+
+        if !OPTICAL_SIZES.contains(&self.optical_size) {
+            return Err(format!(
+                "Icon {} has optical size {}, expected one of {OPTICAL_SIZES:?}",
+                self.name, self.optical_size
+            ));
+        }
+
+        if let Some(Resize {
+            width,
+            height,
+            padding,
+        }) = self.resize
+        {
+            if !(width.is_finite() && width > 0.0 && height.is_finite() && height > 0.0) {
+                return Err(format!(
+                    "Icon {} is resized to {width}x{height}, expected a positive width and height",
+                    self.name
+                ));
+            }
+
+            if !padding.is_finite() {
+                return Err(format!("Icon {} has padding {padding}", self.name));
+            }
+        }
+
+        for (kind, sizes) in [("raster", &self.raster_sizes), ("PNG", &self.png_sizes)] {
+            if let Some(size) = sizes.iter().find(|size| !RASTER_SIZES.contains(size)) {
+                return Err(format!(
+                    "Icon {} has {kind} size {size}, expected a size from {} to {}",
+                    self.name,
+                    RASTER_SIZES.start(),
+                    RASTER_SIZES.end()
+                ));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Suffixes naming the axes that differ from the default variant, e.g.
+    /// `["wght300", "opsz48"]`.
+    fn axes(&self) -> Vec<String> {
+        let mut axes = Vec::new();
+
+        if self.weight != DEFAULT_WEIGHT {
+            axes.push(format!("wght{}", self.weight));
+        }
+
+        if self.grade != DEFAULT_GRADE {
+            // `-` isn't allowed in identifiers, so negative grades are spelled `gradn25`.
+            axes.push(format!("grad{}", self.grade).replace('-', "n"));
+        }
+
+        if self.optical_size != DEFAULT_OPTICAL_SIZE {
+            axes.push(format!("opsz{}", self.optical_size));
+        }
+
+        axes
+    }
+
+    /// Name of the file holding this variant inside the icon's directory.
+    ///
+    /// Only the default axes are shipped as `outlined.svg`, `filled-rounded.svg`
+    /// etc., other variants are looked up as e.g. `rounded-wght300-opsz48.svg`.
+    fn file_name(&self) -> String {
+        let mut stem = format!(
+            "{}{}",
+            if self.filled { "filled-" } else { "" },
+            self.style.as_str()
+        );
+
+        for axis in self.axes() {
+            stem.push('-');
+            stem.push_str(&axis);
+        }
+
+        format!("{stem}.svg")
+    }
+
+    /// Suffix of the constants generated for this variant, e.g. `FILLED_ROUNDED`.
+    fn const_suffix(&self) -> String {
+        let mut suffix = format!(
+            "{}{}",
+            if self.filled { "FILLED_" } else { "" },
+            self.style.as_str()
+        );
+
+        for axis in self.axes() {
+            suffix.push('_');
+            suffix.push_str(&axis);
+        }
+
+        suffix.to_ascii_uppercase()
+    }
+
+    /// Key ordering the variants of an icon in the generated code: by style,
+    /// then fill, then axes.
+    fn sort_key(&self) -> (IconStyle, bool, u16, i16, u8) {
+        (
+            self.style.clone(),
+            self.filled,
+            self.weight,
+            self.grade,
+            self.optical_size,
+        )
+    }
+
+    /// Pattern matching the arguments of the generated functions for this variant.
+    fn pattern(&self) -> String {
+        format!(
+            "(IconStyle::{:?}, {}, {}, {}, {})",
+            self.style, self.filled, self.weight, self.grade, self.optical_size
+        )
+    }
+
+    /// Expression constructing the `IconVariant` describing this variant.
+    fn variant_expr(&self) -> String {
+        format!(
+            "IconVariant {{ style: IconStyle::{:?}, filled: {}, weight: {}, grade: {}, optical_size: {} }}",
+            self.style, self.filled, self.weight, self.grade, self.optical_size
+        )
+    }
 }
 
 impl From<String> for IconInfo {
@@ -38,170 +420,2877 @@ impl From<String> for IconInfo {
             name,
             style: IconStyle::default(),
             filled: false,
+            weight: DEFAULT_WEIGHT,
+            grade: DEFAULT_GRADE,
+            optical_size: DEFAULT_OPTICAL_SIZE,
+            path: None,
+            aliases: Vec::new(),
+            raster_sizes: Vec::new(),
+            png_sizes: Vec::new(),
+            resize: None,
         }
     }
 }
 
-impl From<Icon> for IconInfo {
-    fn from(value: Icon) -> Self {
-        match value {
-            Icon::Simple(name) => name.into(),
-            Icon::Configured(icon_info) => icon_info,
+/// The contents of an icon config, in either of its two forms.
+enum ConfigFile {
+    /// Just the list of icons.
+    Icons(Vec<Icon>),
+    /// The list of icons along with where to look for custom ones.
+    Full(FullConfig),
+}
+
+// Hand written rather than `untagged` so that errors within an entry, such as
+// an unknown field, are reported as is instead of as a failed match.
+impl<'de> Deserialize<'de> for ConfigFile {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct ConfigFileVisitor;
+
+        impl<'de> Visitor<'de> for ConfigFileVisitor {
+            type Value = ConfigFile;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("a list of icons or an object with `icons`")
+            }
+
+            fn visit_seq<A: SeqAccess<'de>>(self, seq: A) -> Result<Self::Value, A::Error> {
+                Vec::deserialize(SeqAccessDeserializer::new(seq)).map(ConfigFile::Icons)
+            }
+
+            fn visit_map<A: MapAccess<'de>>(self, map: A) -> Result<Self::Value, A::Error> {
+                FullConfig::deserialize(MapAccessDeserializer::new(map)).map(ConfigFile::Full)
+            }
         }
+
+        deserializer.deserialize_any(ConfigFileVisitor)
     }
 }
 
 #[derive(Deserialize)]
-#[serde(untagged)]
-enum Icon {
-    Configured(IconInfo),
-    Simple(String),
+#[serde(deny_unknown_fields)]
+struct FullConfig {
+    /// The schema editors should validate the file against, see `icons.schema.json`.
+    #[serde(default, rename = "$schema")]
+    _schema: Option<IgnoredAny>,
+    icons: Vec<Icon>,
+    /// Directories laid out like `icons/`, searched before the shipped icons.
+    #[serde(default)]
+    search_paths: Vec<PathBuf>,
+    /// Size of the box to move the outlines of the icons into, e.g. 24 or 1.
+    #[serde(default)]
+    normalize: Option<f32>,
+    /// How icons are drawn into the PNGs set by `png_sizes`.
+    #[serde(default)]
+    png: PngOptions,
+    /// Whether to embed the SVG documents, which only PNGs and masks may be
+    /// wanted instead of.
+    #[serde(default = "default_svg")]
+    svg: bool,
 }
 
-fn load_icons(dir: &str) -> Result<Vec<IconInfo>, io::Error> {
-    let config_path: PathBuf = [dir, CONFIG_FILE].iter().collect();
-    let config_file = std::fs::read_to_string(config_path)?;
+fn default_svg() -> bool {
+    true
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct PngOptions {
+    /// The color of the icon.
+    #[serde(default = "default_png_color")]
+    color: Color,
+    /// The color behind the icon, transparent if unset.
+    #[serde(default)]
+    background: Option<Color>,
+}
 
-    let icons: Vec<Icon> =
-        serde_json::from_str(&config_file).expect("Couldn't parse icon config file");
+impl Default for PngOptions {
+    fn default() -> Self {
+        Self {
+            color: default_png_color(),
+            background: None,
+        }
+    }
+}
 
-    Ok(icons.into_iter().map(Into::into).collect())
+fn default_png_color() -> Color {
+    Color([0, 0, 0, 255])
 }
 
-fn main() {
-    let out_dir = env::var("OUT_DIR").unwrap();
-    let mut manifest_dir = Path::new(&out_dir).canonicalize().unwrap();
+/// An RGBA color, written `#rgb`, `#rgba`, `#rrggbb` or `#rrggbbaa`.
+#[derive(Clone, Copy)]
+struct Color([u8; 4]);
 
-    eprintln!("Canonical manifest dir: {manifest_dir:?}");
+impl<'de> Deserialize<'de> for Color {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let color = String::deserialize(deserializer)?;
 
-    let (config, config_dir) = if cfg!(docsrs) {
-        if let Ok(source_dir) = env::var("SOURCE_DIR") {
-            (load_icons(&source_dir).unwrap_or_default(), source_dir)
+        parse_hex_color(&color).map(Color).ok_or_else(|| {
+            de::Error::custom(format!(
+                "invalid color {color:?}, expected one such as \"#1f1f1f\" or \"#1f1f1f80\""
+            ))
+        })
+    }
+}
+
+/// Parses a CSS hex color into RGBA.
+fn parse_hex_color(color: &str) -> Option<[u8; 4]> {
+    let digits = color.strip_prefix('#')?;
+
+    if !digits.bytes().all(|digit| digit.is_ascii_hexdigit()) {
+        return None;
+    }
+
+    let channel = |i: usize, len: usize| {
+        let value = u8::from_str_radix(&digits[i * len..(i + 1) * len], 16).unwrap();
+
+        // A single digit is repeated, e.g. `f` is `ff`.
+        if len == 1 {
+            value * 17
         } else {
-            (Vec::new(), "".into())
+            value
         }
-    } else {
-        // Try finding the target directory which is just below the manifest directory
-        // of the user.
-        // Unfortunately, the CARGO_MANIFEST_DIR env var passed by cargo always points
-        // to this crate, so we wouldn't find the users config file this way.
-        while !manifest_dir.join("Cargo.toml").exists() {
-            if !manifest_dir.pop() {
-                panic!("Couldn't find your manifest directory");
-            }
+    };
+
+    match digits.len() {
+        3 => Some([channel(0, 1), channel(1, 1), channel(2, 1), 255]),
+        4 => Some([channel(0, 1), channel(1, 1), channel(2, 1), channel(3, 1)]),
+        6 => Some([channel(0, 2), channel(1, 2), channel(2, 2), 255]),
+        8 => Some([channel(0, 2), channel(1, 2), channel(2, 2), channel(3, 2)]),
+        _ => None,
+    }
+}
+
+/// Just enough of a manifest to get to a `[package.metadata.material-icons]`
+/// section declaring `icons`.
+#[derive(Deserialize)]
+struct PackageManifest {
+    package: ManifestSection,
+}
+
+/// Just enough of a manifest to get to a `[workspace.metadata.material-icons]`
+/// section declaring `icons`.
+#[derive(Deserialize)]
+struct WorkspaceManifest {
+    workspace: ManifestSection,
+}
+
+#[derive(Deserialize)]
+struct ManifestSection {
+    metadata: ManifestMetadata,
+}
+
+#[derive(Deserialize)]
+struct ManifestMetadata {
+    #[serde(rename = "material-icons")]
+    config: FullConfig,
+}
+
+/// The icons to generate code for, along with where to find them.
+struct Config {
+    icons: Vec<IconInfo>,
+    /// Custom icon directories, in order of precedence.
+    search_paths: Vec<PathBuf>,
+    /// Size of the box with its origin at the top left that the outlines of the
+    /// icons are moved into, instead of staying in the units of their viewBox.
+    normalize: Option<f32>,
+    png: PngOptions,
+    /// Whether the SVG documents and the functions returning them are
+    /// generated.
+    svg: bool,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            icons: Vec::new(),
+            search_paths: Vec::new(),
+            normalize: None,
+            png: PngOptions::default(),
+            svg: default_svg(),
         }
+    }
+}
 
-        let config_dir = manifest_dir
-            .to_str()
-            .expect("Couldn't convert manifest directory to string")
-            .to_owned();
-        (
-            load_icons(&config_dir).expect("Couldn't find `icons.json` next to `Cargo.toml`"),
-            config_dir,
-        )
-    };
+enum Icon {
+    Configured(IconEntry),
+    Simple(String),
+}
 
-    eprintln!("Canonical config dir: {config_dir:?}");
+impl<'de> Deserialize<'de> for Icon {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct IconVisitor;
 
-    println!("cargo:rerun-if-changed={config_dir}/icons.json");
+        impl<'de> Visitor<'de> for IconVisitor {
+            type Value = Icon;
 
-    let mut icons: HashMap<String, Vec<(IconInfo, PathBuf)>> = HashMap::new();
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("an icon name or an object with a `name`")
+            }
 
-    for icon in config {
-        let file_name = format!(
-            "{}{}.svg",
-            if icon.filled { "filled-" } else { "" },
-            match icon.style {
-                IconStyle::Outlined => "outlined",
-                IconStyle::Rounded => "rounded",
-                IconStyle::Sharp => "sharp",
-            },
-        );
+            fn visit_str<E: de::Error>(self, name: &str) -> Result<Self::Value, E> {
+                Ok(Icon::Simple(name.to_owned()))
+            }
 
-        let path = PathBuf::from(SHIPPED_ICONS_PATH)
-            .join(&icon.name)
-            .join(file_name);
+            fn visit_map<A: MapAccess<'de>>(self, map: A) -> Result<Self::Value, A::Error> {
+                IconEntry::deserialize(MapAccessDeserializer::new(map)).map(Icon::Configured)
+            }
+        }
 
-        if path.exists() {
-            match icons.entry(icon.name.clone()) {
-                Entry::Occupied(mut entry) => entry.get_mut().push((icon, path)),
-                Entry::Vacant(entry) => {
-                    entry.insert(vec![(icon, path)]);
-                }
+        deserializer.deserialize_any(IconVisitor)
+    }
+}
+
+impl Icon {
+    /// Splits the entry into one `IconInfo` per style and fill it lists.
+    fn expand(self) -> Vec<IconInfo> {
+        let entry = match self {
+            Icon::Simple(name) => return vec![name.into()],
+            Icon::Configured(entry) => entry,
+        };
+
+        let mut icons = Vec::new();
+
+        for style in entry.style.to_vec() {
+            for filled in entry.filled.to_vec() {
+                icons.push(IconInfo {
+                    name: entry.name.clone(),
+                    style: style.clone(),
+                    filled,
+                    weight: entry.weight,
+                    grade: entry.grade,
+                    optical_size: entry.optical_size,
+                    path: entry.path.clone(),
+                    aliases: entry.alias.to_vec(),
+                    raster_sizes: entry.raster_sizes.clone(),
+                    png_sizes: entry.png_sizes.clone(),
+                    resize: entry.resize,
+                });
             }
-        } else {
-            panic!("Icon {} not found at {}", icon.name, path.display());
         }
+
+        icons
     }
+}
 
-    let mut root = Scope::new();
+/// Strict and reserved keywords of the 2021 edition.
+const KEYWORDS: &[&str] = &[
+    "abstract", "as", "async", "await", "become", "box", "break", "const", "continue", "crate",
+    "do", "dyn", "else", "enum", "extern", "false", "final", "fn", "for", "if", "impl", "in",
+    "let", "loop", "macro", "match", "mod", "move", "mut", "override", "priv", "pub", "ref",
+    "return", "self", "Self", "static", "struct", "super", "trait", "true", "try", "type",
+    "typeof", "unsafe", "unsized", "use", "virtual", "where", "while", "yield",
+];
 
-    root.new_enum("IconStyle")
-        .vis("pub")
-        .push_variant(Variant::new("Outlined"))
-        .push_variant(Variant::new("Rounded"))
-        .push_variant(Variant::new("Sharp"));
+/// Keywords that can't be used as raw identifiers either.
+const NON_RAW_KEYWORDS: &[&str] = &["crate", "self", "Self", "super"];
 
-    let mut name_variants = Vec::new();
+/// Rust identifiers generated for a single icon name.
+///
+/// Icon names are directory names under `icons/`, which may start with a digit
+/// (`10k`, `3d_rotation`) or be a keyword (`move`), so they can't be pasted into
+/// the generated code verbatim.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct IconIdents {
+    /// Name of the `icon_{name}` function.
+    function: String,
+    /// Name of the fallible `try_icon_{name}` function.
+    try_function: String,
+    /// Name of the `icon_{name}_mask` function.
+    mask_function: String,
+    /// Name of the `icon_{name}_png` function.
+    png_function: String,
+    /// Prefix of every `ICON_{NAME}_{VARIANT}` constant.
+    constant: String,
+    /// `UpperCamelCase` name used for enum variants.
+    variant: String,
+}
 
-    for (name, variants) in icons {
-        let mut match_variants = Vec::new();
+impl IconIdents {
+    fn new(name: &str) -> Self {
+        let words: Vec<String> = name
+            .split(|c: char| !c.is_ascii_alphanumeric())
+            .filter(|word| !word.is_empty())
+            .map(str::to_ascii_lowercase)
+            .collect();
 
-        for (info, path) in variants {
-            let const_name = format!(
-                "ICON_{}_{}{}",
-                name.to_uppercase(),
-                if info.filled { "FILLED_" } else { "" },
-                match info.style {
-                    IconStyle::Outlined => "OUTLINED",
-                    IconStyle::Rounded => "ROUNDED",
-                    IconStyle::Sharp => "SHARP",
-                }
-            );
+        if words.is_empty() {
+            panic!("Icon name {name:?} doesn't contain any characters usable in an identifier");
+        }
 
-            root.raw(format!(
-                "const {const_name}: &[u8] = include_bytes!(\"{}\");",
-                path.canonicalize().unwrap().display()
-            ));
+        let snake = words.join("_");
+        let camel: String = words
+            .iter()
+            .map(|word| {
+                let mut chars = word.chars();
+                chars
+                    .next()
+                    .map(|first| first.to_ascii_uppercase().to_string() + chars.as_str())
+                    .unwrap_or_default()
+            })
+            .collect();
 
-            match_variants.push(format!(
-                "(IconStyle::{:?}, {}) => {const_name},",
-                info.style, info.filled
-            ));
+        Self {
+            function: escape_ident(format!("icon_{snake}")),
+            try_function: escape_ident(format!("try_icon_{snake}")),
+            mask_function: escape_ident(format!("icon_{snake}_mask")),
+            png_function: escape_ident(format!("icon_{snake}_png")),
+            constant: escape_ident(format!("ICON_{}", snake.to_ascii_uppercase())),
+            variant: escape_ident(if camel.starts_with(|c: char| c.is_ascii_digit()) {
+                format!("Icon{camel}")
+            } else {
+                camel
+            }),
         }
+    }
+}
 
-        let mut func = Function::new(format!("icon_{name}"));
+/// Makes `ident` usable in the generated code, even if it's a keyword.
+fn escape_ident(ident: String) -> String {
+    if NON_RAW_KEYWORDS.contains(&ident.as_str()) {
+        format!("{ident}_")
+    } else if KEYWORDS.contains(&ident.as_str()) {
+        format!("r#{ident}")
+    } else if ident.starts_with(|c: char| c.is_ascii_digit()) {
+        format!("_{ident}")
+    } else {
+        ident
+    }
+}
 
-        func.vis("pub")
-            .arg("style", "IconStyle")
-            .arg("filled", "bool")
-            .ret("&'static [u8]")
-            .line(format!(
-                "match (style, filled) {{
-    {}
-    _ => panic!(\"there is no such icon\")
-}}",
-                match_variants.join("\n")
-            ));
+/// Keeps track of the identifiers already handed out, so that two icon names
+/// that mangle to the same identifier (e.g. `a_1` and `a1`) are reported instead
+/// of producing duplicate definitions.
+#[derive(Default)]
+struct IdentRegistry {
+    taken: HashMap<String, String>,
+}
 
-        root.push_fn(func);
+impl IdentRegistry {
+    fn register(&mut self, name: &str) -> IconIdents {
+        let idents = IconIdents::new(name);
 
-        name_variants.push(format!("{name:?} => icon_{name}(style, filled),"));
+        for ident in [
+            &idents.function,
+            &idents.try_function,
+            &idents.mask_function,
+            &idents.png_function,
+            &idents.constant,
+            &idents.variant,
+        ] {
+            self.claim(ident, name);
+        }
+
+        idents
     }
 
-    root.new_fn("icon")
-        .vis("pub")
-        .arg("name", "impl AsRef<str>")
-        .arg("style", "IconStyle")
-        .arg("filled", "bool")
-        .ret("&'static [u8]")
-        .line(format!(
-            "match name.as_ref() {{
-    {}
-    value => panic!(\"there is no icon called {{value}}\")
-}}",
-            name_variants.join("\n")
-        ));
+    /// Records that `ident` is used by the crate itself, outside of any icon.
+    fn reserve(&mut self, ident: &str) {
+        self.taken.insert(ident.to_owned(), String::new());
+    }
+
+    /// Records that `ident` is generated on behalf of the icon called `name`.
+    fn claim(&mut self, ident: &str, name: &str) {
+        match self.taken.entry(ident.to_owned()) {
+            Entry::Occupied(entry) if entry.get().is_empty() => panic!(
+                "Icon {name:?} maps to the Rust identifier `{ident}`, which the crate already uses"
+            ),
+            Entry::Occupied(entry) if entry.get() != name => panic!(
+                "Icons {:?} and {name:?} both map to the Rust identifier `{ident}`",
+                entry.get()
+            ),
+            Entry::Occupied(_) => {}
+            Entry::Vacant(entry) => {
+                entry.insert(name.to_owned());
+            }
+        }
+    }
+}
+
+/// Returns whether `pattern` contains any wildcards.
+fn is_pattern(pattern: &str) -> bool {
+    pattern.contains(['*', '?'])
+}
+
+/// Returns whether `name` matches `pattern`, where `*` matches any run of
+/// characters and `?` matches exactly one.
+fn matches_wildcard(pattern: &str, name: &str) -> bool {
+    let mut pattern_chars = pattern.chars();
+    let mut name_chars = name.chars();
+
+    match pattern_chars.next() {
+        None => name.is_empty(),
+        Some('*') => name
+            .char_indices()
+            .map(|(i, _)| i)
+            .chain([name.len()])
+            .any(|i| matches_wildcard(pattern_chars.as_str(), &name[i..])),
+        Some('?') => {
+            name_chars.next().is_some()
+                && matches_wildcard(pattern_chars.as_str(), name_chars.as_str())
+        }
+        Some(c) => {
+            name_chars.next() == Some(c)
+                && matches_wildcard(pattern_chars.as_str(), name_chars.as_str())
+        }
+    }
+}
+
+/// Reads a manifest, recording why in `tried` if that isn't possible.
+///
+/// Every manifest read may change where the config is found, so cargo is asked
+/// to rerun the build script when any of them changes.
+fn read_manifest(path: &Path, tried: &mut Vec<String>) -> Option<toml::Table> {
+    println!("cargo:rerun-if-changed={}", path.display());
+
+    let manifest = match fs::read_to_string(path) {
+        Ok(manifest) => manifest,
+        Err(err) => {
+            tried.push(format!("{} (couldn't be read: {err})", path.display()));
+            return None;
+        }
+    };
+
+    match manifest.parse() {
+        Ok(manifest) => Some(manifest),
+        Err(err) => {
+            tried.push(format!("{} (couldn't be parsed: {err})", path.display()));
+            None
+        }
+    }
+}
+
+/// Returns the `[{table}.metadata.material-icons]` section of a manifest.
+fn metadata<'a>(manifest: &'a toml::Table, table: &str) -> Option<&'a toml::Table> {
+    manifest
+        .get(table)?
+        .get("metadata")?
+        .get(METADATA_KEY)?
+        .as_table()
+}
+
+/// Where the icons to generate code for are declared.
+#[derive(Debug)]
+enum ConfigSource {
+    /// An `icons.json` file.
+    File(PathBuf),
+    /// The `icons` key of a `[{table}.metadata.material-icons]` section.
+    Manifest { path: PathBuf, table: &'static str },
+}
+
+impl ConfigSource {
+    fn exists(&self) -> bool {
+        match self {
+            Self::File(path) => path.is_file(),
+            Self::Manifest { .. } => true,
+        }
+    }
+}
+
+impl fmt::Display for ConfigSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::File(path) => path.display().fmt(f),
+            Self::Manifest { path, table } => write!(
+                f,
+                "[{table}.metadata.{METADATA_KEY}] icons in {}",
+                path.display()
+            ),
+        }
+    }
+}
+
+/// Returns the config declared by a `[{table}.metadata.material-icons]` section,
+/// either inline as `icons` or as a `config` path relative to the manifest.
+fn metadata_config(
+    manifest: &toml::Table,
+    table: &'static str,
+    manifest_path: &Path,
+) -> Option<ConfigSource> {
+    let metadata = metadata(manifest, table)?;
+
+    match (metadata.get("icons"), metadata.get("config")) {
+        (Some(_), Some(_)) => panic!(
+            "[{table}.metadata.{METADATA_KEY}] in {} sets both `icons` and `config`, \
+             only one of them may be used",
+            manifest_path.display()
+        ),
+        (Some(_), None) => Some(ConfigSource::Manifest {
+            path: manifest_path.to_path_buf(),
+            table,
+        }),
+        (None, config) => config?.as_str().map(|config| {
+            ConfigSource::File(manifest_path.parent().unwrap_or(Path::new("")).join(config))
+        }),
+    }
+}
+
+/// Lists the directories of every workspace member of a manifest, sorted.
+fn workspace_members(manifest: &toml::Table, dir: &Path) -> Vec<PathBuf> {
+    let members = manifest
+        .get("workspace")
+        .and_then(|workspace| workspace.get("members"))
+        .and_then(|members| members.as_array())
+        .into_iter()
+        .flatten()
+        .filter_map(|member| member.as_str());
+
+    let mut dirs = Vec::new();
+
+    for member in members {
+        let mut candidates = vec![dir.to_path_buf()];
+
+        for component in member.split('/') {
+            candidates = candidates
+                .into_iter()
+                .flat_map(|candidate| {
+                    if !is_pattern(component) {
+                        return vec![candidate.join(component)];
+                    }
+
+                    fs::read_dir(&candidate)
+                        .into_iter()
+                        .flatten()
+                        .filter_map(Result::ok)
+                        .filter(|entry| {
+                            matches_wildcard(component, &entry.file_name().to_string_lossy())
+                        })
+                        .map(|entry| entry.path())
+                        .collect()
+                })
+                .collect();
+        }
+
+        dirs.extend(candidates);
+    }
+
+    dirs.sort();
+    dirs.dedup();
+    dirs
+}
+
+/// Looks for the icon config belonging to the manifest in `dir`.
+///
+/// In order, this checks `[package.metadata.material-icons]`,
+/// `[workspace.metadata.material-icons]` (each either listing the `icons` inline
+/// or pointing at a `config` file), an `icons.json` next to the manifest and
+/// finally the `[package.metadata.material-icons]` of every workspace member.
+fn config_in_manifest(dir: &Path, tried: &mut Vec<String>) -> Option<ConfigSource> {
+    let manifest_path = dir.join("Cargo.toml");
+    let manifest = read_manifest(&manifest_path, tried)?;
+
+    for table in ["package", "workspace"] {
+        if let Some(config) = metadata_config(&manifest, table, &manifest_path) {
+            if config.exists() {
+                return Some(config);
+            }
+
+            tried.push(format!(
+                "{config} (from [{table}.metadata.{METADATA_KEY}] in {})",
+                manifest_path.display()
+            ));
+        } else {
+            tried.push(format!(
+                "[{table}.metadata.{METADATA_KEY}] icons or config in {}",
+                manifest_path.display()
+            ));
+        }
+    }
+
+    let config = dir.join(CONFIG_FILE);
+
+    if config.is_file() {
+        return Some(ConfigSource::File(config));
+    }
+
+    tried.push(config.display().to_string());
+
+    let mut configured = Vec::new();
+
+    for member in workspace_members(&manifest, dir) {
+        let member_manifest = member.join("Cargo.toml");
+
+        if !member_manifest.is_file() {
+            continue;
+        }
+
+        let Some(manifest) = read_manifest(&member_manifest, tried) else {
+            continue;
+        };
+
+        match metadata_config(&manifest, "package", &member_manifest) {
+            Some(config) if config.exists() => configured.push(config),
+            Some(config) => tried.push(format!(
+                "{config} (from [package.metadata.{METADATA_KEY}] in {})",
+                member_manifest.display()
+            )),
+            None => tried.push(format!(
+                "[package.metadata.{METADATA_KEY}] icons or config in {}",
+                member_manifest.display()
+            )),
+        }
+    }
+
+    match configured.len() {
+        0 => None,
+        1 => configured.pop(),
+        _ => panic!(
+            "Several members of the workspace at {} configure icons: {}\n\
+             Pick one with `[workspace.metadata.{METADATA_KEY}] config = \"...\"` or ${CONFIG_ENV}",
+            dir.display(),
+            configured
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+    }
+}
+
+/// Finds the icon config of the crate depending on us.
+///
+/// Cargo doesn't tell dependencies who depends on them, so unless
+/// `MATERIAL_ICONS_CONFIG` points at the config, every manifest above `OUT_DIR`
+/// (the target directory usually lives next to the consumer's manifest) and
+/// above the directory cargo was invoked from (for custom target directories)
+/// is checked with [`config_in_manifest`], nearest first.
+///
+/// Cargo doesn't tell build scripts where it was invoked from either, so that
+/// comes from `$PWD`, as set by the shell. Whether the config was only found
+/// through it is returned along with it.
+fn find_config(out_dir: &Path) -> Result<(ConfigSource, bool), Vec<String>> {
+    println!("cargo:rerun-if-env-changed=PWD");
+
+    let invoked_from = env::var_os("PWD").map(PathBuf::from);
+    let mut tried = Vec::new();
+
+    if let Some(config) = env::var_os(CONFIG_ENV) {
+        let mut config = PathBuf::from(config);
+
+        if config.is_relative() {
+            if let Some(invoked_from) = &invoked_from {
+                config = invoked_from.join(config);
+            }
+        }
+
+        if config.is_dir() {
+            config.push(CONFIG_FILE);
+        }
+
+        if config.is_file() {
+            return Ok((ConfigSource::File(config), false));
+        }
+
+        // An explicitly configured path that doesn't exist is a mistake, falling
+        // back to searching would only hide it.
+        tried.push(format!("{} (from ${CONFIG_ENV})", config.display()));
+
+        return Err(tried);
+    }
+
+    let mut visited = Vec::new();
+
+    let starts = [
+        Some((out_dir.to_path_buf(), "")),
+        invoked_from.map(|dir| (dir, " (from $PWD)")),
+    ];
+
+    for (start, from) in starts.into_iter().flatten() {
+        let has_manifest = start
+            .ancestors()
+            .any(|dir| dir.join("Cargo.toml").is_file());
+
+        if !has_manifest {
+            tried.push(format!("any Cargo.toml above {}{from}", start.display()));
+        }
+
+        for dir in start.ancestors() {
+            if visited.iter().any(|visited| visited == dir) {
+                continue;
+            }
+
+            visited.push(dir.to_path_buf());
+
+            if !dir.join("Cargo.toml").is_file() {
+                continue;
+            }
+
+            let tried_before = tried.len();
+
+            if let Some(config) = config_in_manifest(dir, &mut tried) {
+                return Ok((config, !from.is_empty()));
+            }
+
+            for tried in &mut tried[tried_before..] {
+                tried.push_str(from);
+            }
+        }
+    }
+
+    Err(tried)
+}
+
+/// Reads the icons declared by a config source, resolving relative paths
+/// against the directory of the file declaring them.
+///
+/// Errors are ready to be shown to the user, quoting the offending line of the
+/// config if it couldn't be parsed.
+fn load_icons(source: &ConfigSource) -> Result<Config, String> {
+    let (ConfigSource::File(path) | ConfigSource::Manifest { path, .. }) = source;
+    let contents = fs::read_to_string(path)
+        .map_err(|err| format!("Couldn't read {}: {err}", path.display()))?;
+
+    let config = match source {
+        ConfigSource::File(_) => match serde_json::from_str(&contents) {
+            Ok(ConfigFile::Icons(icons)) => FullConfig {
+                _schema: None,
+                icons,
+                search_paths: Vec::new(),
+                normalize: None,
+                png: PngOptions::default(),
+                svg: default_svg(),
+            },
+            Ok(ConfigFile::Full(config)) => config,
+            Err(err) => return Err(json_error(path, &contents, &err)),
+        },
+        ConfigSource::Manifest { table, .. } => {
+            let config = match *table {
+                "workspace" => toml::from_str(&contents).map(|m: WorkspaceManifest| m.workspace),
+                _ => toml::from_str(&contents).map(|m: PackageManifest| m.package),
+            };
+
+            // toml already quotes the offending line.
+            config
+                .map(|section| section.metadata.config)
+                .map_err(|err| {
+                    format!(
+                        "Couldn't parse [{table}.metadata.{METADATA_KEY}] in {}\n{err}",
+                        path.display()
+                    )
+                })?
+        }
+    };
+
+    let base_dir = path.parent().unwrap_or(Path::new(""));
+    let mut icons: Vec<IconInfo> = config.icons.into_iter().flat_map(Icon::expand).collect();
+
+    for icon in &mut icons {
+        if let Some(path) = &mut icon.path {
+            *path = base_dir.join(&*path);
+        }
+    }
+
+    Ok(Config {
+        icons,
+        search_paths: config
+            .search_paths
+            .iter()
+            .map(|dir| base_dir.join(dir))
+            .collect(),
+        normalize: config.normalize,
+        png: config.png,
+        svg: config.svg,
+    })
+}
+
+/// Formats a JSON parse error the way toml does, quoting the offending line
+/// and pointing at the column the error was detected at.
+fn json_error(path: &Path, contents: &str, err: &serde_json::Error) -> String {
+    let message = err.to_string();
+    let position = format!(" at line {} column {}", err.line(), err.column());
+    let message = message.strip_suffix(&position).unwrap_or(&message);
+
+    let Some(line) = contents.lines().nth(err.line().saturating_sub(1)) else {
+        return format!("Couldn't parse {}: {message}", path.display());
+    };
+
+    let number = err.line().to_string();
+    let gutter = " ".repeat(number.len());
+    let caret = " ".repeat(
+        line[..err.column().min(line.len())]
+            .chars()
+            .count()
+            .saturating_sub(1),
+    );
+
+    format!(
+        "Couldn't parse {}\n\
+         JSON parse error at line {}, column {}\n\
+         {gutter} |\n\
+         {number} | {line}\n\
+         {gutter} | {caret}^\n\
+         {message}",
+        path.display(),
+        err.line(),
+        err.column(),
+    )
+}
+
+/// Finds the SVG file for a variant of an icon.
+///
+/// An entry's own `path` always wins. Otherwise the custom search paths are
+/// tried in the order they're declared in, and the shipped icons last, so that
+/// a custom icon replaces a shipped one of the same name. On failure, every path
+/// that was tried is returned.
+fn locate_icon(icon: &IconInfo, search_paths: &[PathBuf]) -> Result<PathBuf, Vec<PathBuf>> {
+    if let Some(path) = &icon.path {
+        return if path.is_file() {
+            Ok(path.clone())
+        } else {
+            Err(vec![path.clone()])
+        };
+    }
+
+    let candidates: Vec<PathBuf> = search_paths
+        .iter()
+        .map(PathBuf::as_path)
+        .chain([Path::new(SHIPPED_ICONS_PATH)])
+        .map(|dir| dir.join(&icon.name).join(icon.file_name()))
+        .collect();
+
+    match candidates.iter().find(|path| path.is_file()) {
+        Some(path) => Ok(path.clone()),
+        None => Err(candidates),
+    }
+}
+
+/// Explains why no file was found for a variant of an icon: either the icon
+/// doesn't exist at all, in which case similarly named ones are suggested, or
+/// it doesn't come in the requested variant, in which case the ones it does come
+/// in are listed.
+fn missing_icon_message(
+    icon: &IconInfo,
+    search_paths: &[PathBuf],
+    names: &[String],
+    tried: &[PathBuf],
+) -> String {
+    if let Some(path) = &icon.path {
+        return format!("Icon {}: {} doesn't exist", icon.name, path.display());
+    }
+
+    let icon_dirs: Vec<PathBuf> = search_paths
+        .iter()
+        .map(PathBuf::as_path)
+        .chain([Path::new(SHIPPED_ICONS_PATH)])
+        .map(|dir| dir.join(&icon.name))
+        .filter(|dir| dir.is_dir())
+        .collect();
+
+    if icon_dirs.is_empty() {
+        let suggestions = suggest::suggestions(&icon.name, names.iter().map(String::as_str));
+
+        return if suggestions.is_empty() {
+            format!("Icon {} doesn't exist", icon.name)
+        } else {
+            format!(
+                "Icon {} doesn't exist, did you mean {}?",
+                icon.name,
+                suggestions.join(", ")
+            )
+        };
+    }
+
+    let mut variants: Vec<String> = icon_dirs
+        .iter()
+        .flat_map(|dir| fs::read_dir(dir).into_iter().flatten())
+        .filter_map(Result::ok)
+        .filter_map(|entry| entry.file_name().into_string().ok())
+        .filter_map(|file| file.strip_suffix(".svg").map(str::to_owned))
+        .collect();
+
+    variants.sort();
+    variants.dedup();
+
+    format!(
+        "Icon {} has no {} variant, available: {}\n  tried: {}",
+        icon.name,
+        icon.file_name().trim_end_matches(".svg"),
+        variants.join(", "),
+        tried
+            .iter()
+            .map(|path| path.display().to_string())
+            .collect::<Vec<_>>()
+            .join(", ")
+    )
+}
+
+/// Reports every error at once and fails the build.
+fn fail(errors: &[String]) -> ! {
+    for error in errors {
+        eprintln!("error: {error}");
+    }
+
+    if errors.len() > 1 {
+        eprintln!("\n{} invalid icon entries, see above", errors.len());
+    }
+
+    process::exit(1)
+}
+
+/// Points an entry still using an icon's name from before an upstream rename
+/// at the icon's current name, keeping the old one as an alias.
+fn follow_rename(mut icon: IconInfo, search_paths: &[PathBuf]) -> IconInfo {
+    if icon.path.is_some() || locate_icon(&icon, search_paths).is_ok() {
+        return icon;
+    }
+
+    if let Some((old, new)) = RENAMED_ICONS.iter().find(|(old, _)| *old == icon.name) {
+        println!("cargo:warning=Icon {old} has been renamed to {new}, using that instead");
+
+        icon.name = new.to_string();
+        icon.aliases.push(old.to_string());
+    }
+
+    icon
+}
+
+/// Lists the names of every shipped icon, sorted.
+fn shipped_icon_names() -> Vec<String> {
+    let mut names: Vec<String> = fs::read_dir(SHIPPED_ICONS_PATH)
+        .expect("Couldn't list the shipped icons")
+        .map(|entry| {
+            entry
+                .expect("Couldn't list the shipped icons")
+                .file_name()
+                .into_string()
+                .expect("Shipped icon names are UTF-8")
+        })
+        .collect();
+
+    names.sort();
+    names
+}
+
+/// Lists the names of every icon in the custom search paths and the shipped
+/// ones, sorted and without duplicates.
+fn icon_names(search_paths: &[PathBuf]) -> Vec<String> {
+    let mut names = shipped_icon_names();
+
+    for dir in search_paths {
+        names.extend(
+            fs::read_dir(dir)
+                .into_iter()
+                .flatten()
+                .filter_map(Result::ok)
+                .filter(|entry| entry.path().is_dir())
+                .filter_map(|entry| entry.file_name().into_string().ok()),
+        );
+    }
+
+    names.sort();
+    names.dedup();
+    names
+}
+
+/// Replaces every entry whose name is a pattern like `arrow_*` with one entry
+/// per matching icon, in alphabetical order, keeping the rest of the entry's
+/// settings.
+fn expand_patterns(config: Vec<IconInfo>, search_paths: &[PathBuf]) -> Vec<IconInfo> {
+    if !config.iter().any(|icon| is_pattern(&icon.name)) {
+        return config;
+    }
+
+    let available = icon_names(search_paths);
+    let mut icons = Vec::new();
+
+    for icon in config {
+        if !is_pattern(&icon.name) {
+            icons.push(icon);
+            continue;
+        }
+
+        let matches: Vec<&String> = available
+            .iter()
+            .filter(|name| matches_wildcard(&icon.name, name))
+            .collect();
+
+        if matches.is_empty() {
+            println!(
+                "cargo:warning=Icon pattern {:?} doesn't match any icon",
+                icon.name
+            );
+        }
+
+        for name in matches {
+            icons.push(IconInfo {
+                name: name.clone(),
+                ..icon.clone()
+            });
+        }
+    }
+
+    icons
+}
+
+/// Lists every shipped icon variant enabled through the `all-outlined`,
+/// `all-rounded` and `all-sharp` features, including the filled ones if the
+/// `filled` feature is enabled as well.
+fn bundled_icons() -> Vec<IconInfo> {
+    let styles: Vec<IconStyle> = [
+        ("CARGO_FEATURE_ALL_OUTLINED", IconStyle::Outlined),
+        ("CARGO_FEATURE_ALL_ROUNDED", IconStyle::Rounded),
+        ("CARGO_FEATURE_ALL_SHARP", IconStyle::Sharp),
+    ]
+    .into_iter()
+    .filter(|(feature, _)| env::var_os(feature).is_some())
+    .map(|(_, style)| style)
+    .collect();
+
+    if styles.is_empty() {
+        return Vec::new();
+    }
+
+    let fills: &[bool] = if env::var_os("CARGO_FEATURE_FILLED").is_some() {
+        &[false, true]
+    } else {
+        &[false]
+    };
+
+    let mut icons = Vec::new();
+
+    for name in shipped_icon_names() {
+        for style in &styles {
+            for &filled in fills {
+                icons.push(IconInfo {
+                    style: style.clone(),
+                    filled,
+                    ..IconInfo::from(name.clone())
+                });
+            }
+        }
+    }
+
+    icons
+}
+
+/// A segment of an icon's outline in absolute coordinates, mirroring the
+/// runtime `PathCommand` so that its `Debug` output is the Rust expression
+/// building it.
+#[derive(Debug, Clone, Copy)]
+enum PathCommand {
+    MoveTo {
+        x: f32,
+        y: f32,
+    },
+    LineTo {
+        x: f32,
+        y: f32,
+    },
+    Cubic {
+        x1: f32,
+        y1: f32,
+        x2: f32,
+        y2: f32,
+        x: f32,
+        y: f32,
+    },
+    Quad {
+        x1: f32,
+        y1: f32,
+        x: f32,
+        y: f32,
+    },
+    Arc {
+        rx: f32,
+        ry: f32,
+        x_axis_rotation: f32,
+        large_arc: bool,
+        sweep: bool,
+        x: f32,
+        y: f32,
+    },
+    Close,
+}
+
+impl fmt::Display for PathCommand {
+    /// Formats the command as absolute SVG path data.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            Self::MoveTo { x, y } => write!(f, "M{x} {y}"),
+            Self::LineTo { x, y } => write!(f, "L{x} {y}"),
+            Self::Cubic {
+                x1,
+                y1,
+                x2,
+                y2,
+                x,
+                y,
+            } => write!(f, "C{x1} {y1} {x2} {y2} {x} {y}"),
+            Self::Quad { x1, y1, x, y } => write!(f, "Q{x1} {y1} {x} {y}"),
+            Self::Arc {
+                rx,
+                ry,
+                x_axis_rotation,
+                large_arc,
+                sweep,
+                x,
+                y,
+            } => write!(
+                f,
+                "A{rx} {ry} {x_axis_rotation} {} {} {x} {y}",
+                u8::from(large_arc),
+                u8::from(sweep)
+            ),
+            Self::Close => f.write_str("Z"),
+        }
+    }
+}
+
+/// Reads the numbers and flags of SVG path data.
+struct PathLexer<'a> {
+    data: &'a str,
+    pos: usize,
+}
+
+impl PathLexer<'_> {
+    fn skip_separators(&mut self) {
+        let rest = &self.data[self.pos..];
+
+        self.pos += rest.len()
+            - rest
+                .trim_start_matches(|c: char| c.is_ascii_whitespace() || c == ',')
+                .len();
+    }
+
+    fn is_done(&mut self) -> bool {
+        self.skip_separators();
+        self.pos == self.data.len()
+    }
+
+    /// Consumes the next command letter, if the next token is one.
+    fn command(&mut self) -> Option<char> {
+        self.skip_separators();
+
+        let letter = self.data[self.pos..]
+            .chars()
+            .next()
+            .filter(|c| c.is_ascii_alphabetic() && !matches!(c, 'e' | 'E'))?;
+
+        self.pos += 1;
+
+        Some(letter)
+    }
+
+    fn number(&mut self) -> Result<f32, String> {
+        self.skip_separators();
+
+        let bytes = self.data.as_bytes();
+        let start = self.pos;
+        let mut end = start;
+        let digits = |end: &mut usize| {
+            let from = *end;
+
+            while bytes.get(*end).is_some_and(u8::is_ascii_digit) {
+                *end += 1;
+            }
+
+            *end > from
+        };
+
+        if matches!(bytes.get(end), Some(b'+' | b'-')) {
+            end += 1;
+        }
+
+        let mut mantissa = digits(&mut end);
+
+        if bytes.get(end) == Some(&b'.') {
+            end += 1;
+            mantissa |= digits(&mut end);
+        }
+
+        if !mantissa {
+            return Err(format!("expected a number at offset {start}"));
+        }
+
+        if matches!(bytes.get(end), Some(b'e' | b'E')) {
+            let mut exponent = end + 1;
+
+            if matches!(bytes.get(exponent), Some(b'+' | b'-')) {
+                exponent += 1;
+            }
+
+            if digits(&mut exponent) {
+                end = exponent;
+            }
+        }
+
+        self.pos = end;
+
+        let number: f32 = self.data[start..end]
+            .parse()
+            .map_err(|err| format!("invalid number at offset {start}: {err}"))?;
+
+        // Too large a number parses as infinity, which can't be written out.
+        if !number.is_finite() {
+            return Err(format!("number out of range at offset {start}"));
+        }
+
+        Ok(number)
+    }
+
+    fn point(&mut self) -> Result<(f32, f32), String> {
+        Ok((self.number()?, self.number()?))
+    }
+
+    /// Reads an arc flag, which may be written without any separator after it.
+    fn flag(&mut self) -> Result<bool, String> {
+        self.skip_separators();
+
+        let flag = match self.data.as_bytes().get(self.pos) {
+            Some(b'0') => false,
+            Some(b'1') => true,
+            _ => return Err(format!("expected a flag at offset {}", self.pos)),
+        };
+
+        self.pos += 1;
+
+        Ok(flag)
+    }
+}
+
+/// Parses SVG path data into absolute commands, resolving relative commands
+/// and the shorthands `H`, `V`, `S` and `T`.
+fn parse_path(data: &str) -> Result<Vec<PathCommand>, String> {
+    let mut lexer = PathLexer { data, pos: 0 };
+    let mut commands = Vec::new();
+    let mut current = (0.0, 0.0);
+    let mut start = (0.0, 0.0);
+    let mut previous = None;
+    // Second control point of the previous curve, reflected by `S` and `T`.
+    let mut last_control = None;
+
+    while !lexer.is_done() {
+        let letter = match (lexer.command(), previous) {
+            (Some(letter), _) => letter,
+            // Coordinates following a move are implicit lines.
+            (None, Some('M')) => 'L',
+            (None, Some('m')) => 'l',
+            (None, Some(letter)) if !matches!(letter, 'Z' | 'z') => letter,
+            _ => return Err(format!("expected a command at offset {}", lexer.pos)),
+        };
+
+        if commands.is_empty() && !matches!(letter, 'M' | 'm') {
+            return Err(format!(
+                "path data starts with {letter:?} instead of a move"
+            ));
+        }
+
+        let (dx, dy) = if letter.is_ascii_lowercase() {
+            current
+        } else {
+            (0.0, 0.0)
+        };
+        let point =
+            |lexer: &mut PathLexer| lexer.point().map(|(x, y): (f32, f32)| (x + dx, y + dy));
+
+        let (command, control) = match letter.to_ascii_uppercase() {
+            'M' => {
+                let (x, y) = point(&mut lexer)?;
+
+                start = (x, y);
+
+                (PathCommand::MoveTo { x, y }, None)
+            }
+            'L' => {
+                let (x, y) = point(&mut lexer)?;
+
+                (PathCommand::LineTo { x, y }, None)
+            }
+            'H' => {
+                let x = lexer.number()? + dx;
+
+                (PathCommand::LineTo { x, y: current.1 }, None)
+            }
+            'V' => {
+                let y = lexer.number()? + dy;
+
+                (PathCommand::LineTo { x: current.0, y }, None)
+            }
+            'C' | 'S' => {
+                let (x1, y1) = if letter.eq_ignore_ascii_case(&'C') {
+                    point(&mut lexer)?
+                } else {
+                    reflect(current, last_control.filter(|_| is_cubic(previous)))
+                };
+                let (x2, y2) = point(&mut lexer)?;
+                let (x, y) = point(&mut lexer)?;
+
+                (
+                    PathCommand::Cubic {
+                        x1,
+                        y1,
+                        x2,
+                        y2,
+                        x,
+                        y,
+                    },
+                    Some((x2, y2)),
+                )
+            }
+            'Q' | 'T' => {
+                let (x1, y1) = if letter.eq_ignore_ascii_case(&'Q') {
+                    point(&mut lexer)?
+                } else {
+                    reflect(current, last_control.filter(|_| is_quad(previous)))
+                };
+                let (x, y) = point(&mut lexer)?;
+
+                (PathCommand::Quad { x1, y1, x, y }, Some((x1, y1)))
+            }
+            'A' => {
+                let rx = lexer.number()?;
+                let ry = lexer.number()?;
+                let x_axis_rotation = lexer.number()?;
+                let large_arc = lexer.flag()?;
+                let sweep = lexer.flag()?;
+                let (x, y) = point(&mut lexer)?;
+
+                (
+                    PathCommand::Arc {
+                        rx,
+                        ry,
+                        x_axis_rotation,
+                        large_arc,
+                        sweep,
+                        x,
+                        y,
+                    },
+                    None,
+                )
+            }
+            'Z' => (PathCommand::Close, None),
+            _ => return Err(format!("unknown command {letter:?}")),
+        };
+
+        current = match command {
+            PathCommand::MoveTo { x, y }
+            | PathCommand::LineTo { x, y }
+            | PathCommand::Cubic { x, y, .. }
+            | PathCommand::Quad { x, y, .. }
+            | PathCommand::Arc { x, y, .. } => (x, y),
+            PathCommand::Close => start,
+        };
+
+        commands.push(command);
+        previous = Some(letter);
+        last_control = control;
+    }
+
+    Ok(commands)
+}
+
+fn is_cubic(command: Option<char>) -> bool {
+    matches!(command, Some('C' | 'c' | 'S' | 's'))
+}
+
+fn is_quad(command: Option<char>) -> bool {
+    matches!(command, Some('Q' | 'q' | 'T' | 't'))
+}
+
+/// Reflects a control point about the current point, which is the control
+/// point itself when there's none to reflect.
+fn reflect(current: (f32, f32), control: Option<(f32, f32)>) -> (f32, f32) {
+    match control {
+        Some((x, y)) => (2.0 * current.0 - x, 2.0 * current.1 - y),
+        None => current,
+    }
+}
+
+/// Returns the `d` attribute of every filled `<path>` element of an SVG
+/// document.
+///
+/// Paths with `fill="none"`, such as the transparent padding squares of older
+/// Material icons, don't draw anything and so are left out of the outline.
+fn path_data(svg: &str) -> Vec<&str> {
+    svg::tags(svg, "path")
+        .filter(|tag| {
+            let unfilled = tag.get("fill").is_some_and(|fill| fill.trim() == "none")
+                || tag.get("style").is_some_and(|style| {
+                    style.split(';').any(|declaration| {
+                        declaration.split_once(':').is_some_and(|(name, value)| {
+                            name.trim() == "fill" && value.trim() == "none"
+                        })
+                    })
+                });
+
+            !unfilled
+        })
+        .filter_map(|tag| tag.get("d"))
+        .collect()
+}
+
+/// Moves commands from the given viewBox into a box of `size` units with its
+/// origin at the top left, scaling them uniformly and centering them along the
+/// shorter side of the viewBox.
+fn normalize_path(commands: &mut [PathCommand], view_box: [f32; 4], size: f32) {
+    // Computed in f64 so that e.g. 154 / 40 comes out as 3.85 rather than
+    // 3.8500001.
+    let [min_x, min_y, width, height] = view_box.map(f64::from);
+    let size = f64::from(size);
+    let scale = size / width.max(height);
+    let offset_x = (size - width * scale) / 2.0;
+    let offset_y = (size - height * scale) / 2.0;
+    let point = |x: &mut f32, y: &mut f32| {
+        *x = ((f64::from(*x) - min_x) * scale + offset_x) as f32;
+        *y = ((f64::from(*y) - min_y) * scale + offset_y) as f32;
+    };
+
+    for command in commands {
+        match command {
+            PathCommand::MoveTo { x, y } | PathCommand::LineTo { x, y } => point(x, y),
+            PathCommand::Cubic {
+                x1,
+                y1,
+                x2,
+                y2,
+                x,
+                y,
+            } => {
+                point(x1, y1);
+                point(x2, y2);
+                point(x, y);
+            }
+            PathCommand::Quad { x1, y1, x, y } => {
+                point(x1, y1);
+                point(x, y);
+            }
+            PathCommand::Arc { rx, ry, x, y, .. } => {
+                *rx = (f64::from(*rx) * scale) as f32;
+                *ry = (f64::from(*ry) * scale) as f32;
+                point(x, y);
+            }
+            PathCommand::Close => {}
+        }
+    }
+}
+
+/// Extracts the outline of an icon from its SVG document, as path data and
+/// parsed commands, normalized into a box of the given size if any.
+///
+/// Icons drawn with several paths have them joined, in which case the path
+/// data is rewritten from the parsed commands since relative commands at the
+/// start of a path would otherwise change meaning. So is normalized path data.
+fn icon_path(svg: &str, normalize: Option<f32>) -> Result<(String, Vec<PathCommand>), String> {
+    let data = path_data(svg);
+    let mut commands = Vec::new();
+
+    if data.is_empty() {
+        return Err("no filled <path> element".to_owned());
+    }
+
+    for data in &data {
+        commands.extend(parse_path(data)?);
+    }
+
+    if let Some(size) = normalize {
+        let view_box = svg::root(svg)
+            .and_then(|root| root.view_box())
+            .filter(|[_, _, width, height]| *width > 0.0 && *height > 0.0)
+            .ok_or("no usable viewBox, width or height to normalize it from")?;
+
+        normalize_path(&mut commands, view_box, size);
+
+        return Ok((format_path(&commands), commands));
+    }
+
+    match data[..] {
+        [data] => Ok((data.to_owned(), commands)),
+        _ => Ok((format_path(&commands), commands)),
+    }
+}
+
+/// Formats commands as absolute SVG path data.
+fn format_path(commands: &[PathCommand]) -> String {
+    commands
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// An axis-aligned rectangle, mirroring the runtime `Rect`.
+// Only ever read through `Debug`, which is the Rust expression building it.
+#[allow(dead_code)]
+#[derive(Debug, Clone, Copy, Default)]
+struct Rect {
+    x: f32,
+    y: f32,
+    width: f32,
+    height: f32,
+}
+
+impl From<[f32; 4]> for Rect {
+    fn from([x, y, width, height]: [f32; 4]) -> Self {
+        Self {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+}
+
+/// Layout information about an icon variant, mirroring the runtime `IconMeta`.
+// Only ever read through `Debug`, which is the Rust expression building it.
+#[allow(dead_code)]
+#[derive(Debug)]
+struct IconMeta {
+    view_box: Rect,
+    width: f32,
+    height: f32,
+    bounding_box: Rect,
+}
+
+/// Reads the layout information of an icon from its SVG document and outline.
+fn icon_meta(svg: &str, commands: &[PathCommand]) -> IconMeta {
+    let root = svg::root(svg);
+    let view_box = root
+        .as_ref()
+        .and_then(|root| root.view_box())
+        .unwrap_or_default();
+    let length = |name| root.as_ref()?.length(name);
+
+    IconMeta {
+        view_box: view_box.into(),
+        width: length("width").unwrap_or(view_box[2]),
+        height: length("height").unwrap_or(view_box[3]),
+        bounding_box: bounding_box(commands),
+    }
+}
+
+/// Computes the tight bounding box of an outline, taking the extrema of its
+/// curves into account rather than just their control points.
+fn bounding_box(commands: &[PathCommand]) -> Rect {
+    let mut points = Vec::new();
+    let mut current = (0.0, 0.0);
+    let mut start = (0.0, 0.0);
+
+    for command in commands {
+        let end = match *command {
+            PathCommand::MoveTo { x, y } => {
+                start = (f64::from(x), f64::from(y));
+                start
+            }
+            PathCommand::LineTo { x, y } => (f64::from(x), f64::from(y)),
+            PathCommand::Quad { x1, y1, x, y } => {
+                let p0 = current;
+                let [p1, p2] = [(x1, y1), (x, y)].map(|(x, y)| (f64::from(x), f64::from(y)));
+
+                for t in quad_extrema(p0.0, p1.0, p2.0).chain(quad_extrema(p0.1, p1.1, p2.1)) {
+                    let at = |a: f64, b: f64, c: f64| {
+                        (1.0 - t) * (1.0 - t) * a + 2.0 * (1.0 - t) * t * b + t * t * c
+                    };
+
+                    points.push((at(p0.0, p1.0, p2.0), at(p0.1, p1.1, p2.1)));
+                }
+
+                p2
+            }
+            PathCommand::Cubic {
+                x1,
+                y1,
+                x2,
+                y2,
+                x,
+                y,
+            } => {
+                let p0 = current;
+                let [p1, p2, p3] =
+                    [(x1, y1), (x2, y2), (x, y)].map(|(x, y)| (f64::from(x), f64::from(y)));
+
+                for t in cubic_extrema(p0.0, p1.0, p2.0, p3.0)
+                    .into_iter()
+                    .chain(cubic_extrema(p0.1, p1.1, p2.1, p3.1))
+                {
+                    let at = |a: f64, b: f64, c: f64, d: f64| {
+                        let s = 1.0 - t;
+
+                        s * s * s * a + 3.0 * s * s * t * b + 3.0 * s * t * t * c + t * t * t * d
+                    };
+
+                    points.push((at(p0.0, p1.0, p2.0, p3.0), at(p0.1, p1.1, p2.1, p3.1)));
+                }
+
+                p3
+            }
+            PathCommand::Arc {
+                rx,
+                ry,
+                x_axis_rotation,
+                large_arc,
+                sweep,
+                x,
+                y,
+            } => {
+                let end = (f64::from(x), f64::from(y));
+
+                points.extend(arc_extrema(
+                    current,
+                    end,
+                    f64::from(rx),
+                    f64::from(ry),
+                    f64::from(x_axis_rotation),
+                    large_arc,
+                    sweep,
+                ));
+
+                end
+            }
+            PathCommand::Close => start,
+        };
+
+        points.push(end);
+        current = end;
+    }
+
+    let Some(&(first_x, first_y)) = points.first() else {
+        return Rect::default();
+    };
+    let (mut min_x, mut min_y, mut max_x, mut max_y) = (first_x, first_y, first_x, first_y);
+
+    for (x, y) in points {
+        min_x = min_x.min(x);
+        min_y = min_y.min(y);
+        max_x = max_x.max(x);
+        max_y = max_y.max(y);
+    }
+
+    Rect {
+        x: min_x as f32,
+        y: min_y as f32,
+        width: (max_x - min_x) as f32,
+        height: (max_y - min_y) as f32,
+    }
+}
+
+/// Values of `t` strictly inside the curve at which a quadratic Bézier curve
+/// with the given coordinates along one axis turns around.
+fn quad_extrema(p0: f64, p1: f64, p2: f64) -> impl Iterator<Item = f64> {
+    let denominator = p0 - 2.0 * p1 + p2;
+
+    (denominator != 0.0)
+        .then(|| (p0 - p1) / denominator)
+        .filter(|t| *t > 0.0 && *t < 1.0)
+        .into_iter()
+}
+
+/// Values of `t` strictly inside the curve at which a cubic Bézier curve with
+/// the given coordinates along one axis turns around.
+fn cubic_extrema(p0: f64, p1: f64, p2: f64, p3: f64) -> Vec<f64> {
+    // The derivative, divided by 3, is a t² + b t + c.
+    let a = -p0 + 3.0 * p1 - 3.0 * p2 + p3;
+    let b = 2.0 * (p0 - 2.0 * p1 + p2);
+    let c = p1 - p0;
+
+    let roots = if a.abs() < 1e-12 {
+        if b == 0.0 {
+            Vec::new()
+        } else {
+            vec![-c / b]
+        }
+    } else {
+        let discriminant = b * b - 4.0 * a * c;
+
+        if discriminant < 0.0 {
+            Vec::new()
+        } else {
+            let root = discriminant.sqrt();
+
+            vec![(-b + root) / (2.0 * a), (-b - root) / (2.0 * a)]
+        }
+    };
+
+    roots.into_iter().filter(|t| *t > 0.0 && *t < 1.0).collect()
+}
+
+/// Points at which an SVG arc turns around along either axis.
+fn arc_extrema(
+    from: (f64, f64),
+    to: (f64, f64),
+    rx: f64,
+    ry: f64,
+    x_axis_rotation: f64,
+    large_arc: bool,
+    sweep: bool,
+) -> Vec<(f64, f64)> {
+    // A degenerate arc is a straight line.
+    let Some(arc) = raster::CenterArc::new(from, (rx, ry), x_axis_rotation, (large_arc, sweep), to)
+    else {
+        return Vec::new();
+    };
+    let ((rx, ry), (sin, cos)) = (arc.radii, arc.rotation);
+    let along_x = (-ry * sin).atan2(rx * cos);
+    let along_y = (ry * cos).atan2(rx * sin);
+
+    [along_x, along_x + PI, along_y, along_y + PI]
+        .into_iter()
+        .filter(|theta| {
+            // How far along the arc's direction the angle is from its start.
+            let offset = (theta - arc.start).rem_euclid(TAU);
+            let offset = if arc.delta < 0.0 {
+                TAU - offset
+            } else {
+                offset
+            };
+
+            offset <= arc.delta.abs()
+        })
+        .map(|theta| arc.point(theta))
+        .collect()
+}
+
+/// An icon that code has been generated for.
+struct GeneratedIcon {
+    name: String,
+    idents: IconIdents,
+    /// Name of the constant listing the icon's variants.
+    variants_const: String,
+    /// Name of the static listing the outline of each variant, in the same
+    /// order.
+    paths_static: String,
+    /// Name of the static listing the layout information of each variant, in
+    /// the same order.
+    metas_static: String,
+    /// Name of the static listing the masks rasterized while building, along
+    /// with the variant each belongs to.
+    masks_static: String,
+    /// Name of the static listing the PNGs encoded while building, along with
+    /// the variant and size of each.
+    pngs_static: String,
+    /// Other names the icon is available under, sorted.
+    aliases: Vec<String>,
+}
+
+/// A distinct SVG file statics have been generated for.
+struct IconFile {
+    /// Name of the static holding the file, which the names of the statics
+    /// derived from it start with.
+    static_name: String,
+    /// The outline of the icon.
+    commands: Vec<PathCommand>,
+    /// The box the commands are laid out in, as `[x, y, width, height]`.
+    view_box: [f32; 4],
+    /// Sizes the icon has been rasterized at.
+    raster_sizes: HashSet<u32>,
+    /// Sizes the icon has been encoded as PNG at.
+    png_sizes: HashSet<u32>,
+}
+
+/// Emits the `IconStyle` enum along with its trait implementations.
+fn push_icon_style(root: &mut Scope) {
+    const STYLES: [(&str, &str); 3] = [
+        ("Outlined", "outlined"),
+        ("Rounded", "rounded"),
+        ("Sharp", "sharp"),
+    ];
+
+    let icon_style = root
+        .new_enum("IconStyle")
+        .vis("pub")
+        .doc("Visual style of an icon.")
+        .derive("Debug")
+        .derive("Default")
+        .derive("Clone")
+        .derive("Copy")
+        .derive("PartialEq")
+        .derive("Eq")
+        .derive("Hash")
+        .derive("PartialOrd")
+        .derive("Ord")
+        .r#macro("#[cfg_attr(feature = \"serde\", derive(serde::Serialize, serde::Deserialize))]")
+        .r#macro("#[cfg_attr(feature = \"serde\", serde(rename_all = \"lowercase\"))]");
+
+    for (i, (variant, _)) in STYLES.iter().enumerate() {
+        let variant = icon_style.new_variant(*variant);
+
+        if i == 0 {
+            variant.annotation("#[default]");
+        }
+    }
+
+    let arms = |f: &dyn Fn(&str, &str) -> String| {
+        STYLES
+            .iter()
+            .map(|(variant, name)| f(variant, name))
+            .collect::<Vec<_>>()
+            .join("\n")
+    };
+
+    let style_impl = root.new_impl("IconStyle");
+
+    style_impl.associate_const(
+        "ALL",
+        "[Self; 3]",
+        format!("[{}]", arms(&|variant, _| format!("Self::{variant},"))),
+        "pub",
+    );
+
+    style_impl
+        .new_fn("as_str")
+        .doc("Returns the name of the style as spelled in icons.json, e.g. `\"rounded\"`.")
+        .vis("pub const")
+        .arg_self()
+        .ret("&'static str")
+        .line(format!(
+            "match self {{\n{}\n}}",
+            arms(&|variant, name| format!("Self::{variant} => {name:?},"))
+        ));
+
+    root.new_impl("IconStyle")
+        .impl_trait("core::str::FromStr")
+        .associate_type("Err", "crate::ParseIconStyleError")
+        .new_fn("from_str")
+        .arg("s", "&str")
+        .ret("Result<Self, Self::Err>")
+        .line(format!(
+            "match s {{\n{}\nvalue => Err(crate::ParseIconStyleError::new(value)),\n}}",
+            arms(&|variant, name| format!("{name:?} => Ok(Self::{variant}),"))
+        ));
+
+    root.new_impl("IconStyle")
+        .impl_trait("core::fmt::Display")
+        .new_fn("fmt")
+        .arg_ref_self()
+        .arg("f", "&mut core::fmt::Formatter<'_>")
+        .ret("core::fmt::Result")
+        .line("f.write_str(self.as_str())");
+}
+
+/// Emits the `icon!` macro, which hands the location of the shipped icons to the
+/// proc-macro doing the actual work.
+fn push_icon_macro(root: &mut Scope) {
+    let icons_dir = Path::new(&env::var("CARGO_MANIFEST_DIR").unwrap()).join(SHIPPED_ICONS_PATH);
+
+    root.raw(format!(
+        "/// Expands to the SVG document of a shipped icon as `&'static [u8]`,
+/// without it having to be listed in the icon config.
+///
+/// Takes the icon name followed by any of a style (`outlined`, `rounded` or
+/// `sharp`), `filled`, `weight = ..`, `grade = ..` and `optical_size = ..`.
+/// Unknown icons and variants are reported at compile time.
+///
+/// ```
+/// let svg: &[u8] = material_icons::icon!(home, rounded, filled);
+/// ```
+#[cfg(feature = \"macros\")]
+#[macro_export]
+macro_rules! icon {{
+    ($($args:tt)*) => {{
+        $crate::__macros::icon!({:?}; $($args)*)
+    }};
+}}",
+        icons_dir.display().to_string()
+    ));
+}
+
+/// Emits the `IconName` enum with one variant per configured icon.
+fn push_icon_name(root: &mut Scope, icons: &[GeneratedIcon], geometry: bool, svg: bool) {
+    let icon_name = root
+        .new_enum("IconName")
+        .vis("pub")
+        .doc("Name of an icon that was compiled into this crate.")
+        .derive("Debug")
+        .derive("Clone")
+        .derive("Copy")
+        .derive("PartialEq")
+        .derive("Eq")
+        .derive("Hash")
+        .derive("PartialOrd")
+        .derive("Ord");
+
+    for icon in icons {
+        icon_name.new_variant(&icon.idents.variant);
+    }
+
+    let variants =
+        |f: &dyn Fn(&GeneratedIcon) -> String| icons.iter().map(f).collect::<Vec<_>>().join("\n");
+
+    let all = format!(
+        "&[{}]",
+        variants(&|icon| format!("Self::{},", icon.idents.variant))
+    );
+
+    let name_impl = root.new_impl("IconName");
+
+    name_impl.associate_const("ALL", "&'static [Self]", all, "pub");
+
+    name_impl
+        .new_fn("as_str")
+        .doc("Returns the Material name of the icon, e.g. `\"3d_rotation\"`.")
+        .vis("pub const")
+        .arg_self()
+        .ret("&'static str")
+        .line(format!(
+            "match self {{\n{}\n}}",
+            variants(&|icon| format!("Self::{} => {:?},", icon.idents.variant, icon.name))
+        ));
+
+    name_impl
+        .new_fn("aliases")
+        .doc("Returns the other names this icon can be looked up by, such as names it had before being renamed upstream.")
+        .vis("pub const")
+        .arg_self()
+        .ret("&'static [&'static str]")
+        .line(format!(
+            "match self {{\n{}\n}}",
+            variants(&|icon| format!("Self::{} => &{:?},", icon.idents.variant, icon.aliases))
+        ));
+
+    name_impl
+        .new_fn("variants")
+        .doc("Returns every variant of this icon that was compiled in.")
+        .vis("pub const")
+        .arg_self()
+        .ret("&'static [IconVariant]")
+        .line(format!(
+            "match self {{\n{}\n}}",
+            variants(&|icon| format!("Self::{} => {},", icon.idents.variant, icon.variants_const))
+        ));
+
+    name_impl
+        .new_fn("has_variant")
+        .doc("Returns whether the given variant of this icon was compiled in.")
+        .vis("pub")
+        .arg_self()
+        .arg("style", "IconStyle")
+        .arg("filled", "bool")
+        .arg("weight", "u16")
+        .arg("grade", "i16")
+        .arg("optical_size", "u8")
+        .ret("bool")
+        .line(
+            "self.variants().contains(&IconVariant { style, filled, weight, grade, optical_size })",
+        );
+
+    if svg {
+        name_impl
+            .new_fn("try_icon")
+            .doc("Returns the SVG document of the given variant of this icon, or an error if that variant wasn't compiled in.")
+            .vis("pub")
+            .arg_self()
+            .arg("style", "IconStyle")
+            .arg("filled", "bool")
+            .arg("weight", "u16")
+            .arg("grade", "i16")
+            .arg("optical_size", "u8")
+            .ret("Result<&'static [u8], IconError>")
+            .line(if icons.is_empty() {
+                // Without icons, `self` can't exist and the arguments go unused.
+                "let _ = (style, filled, weight, grade, optical_size);\n\nmatch self {}".to_owned()
+            } else {
+                format!(
+                    "match self {{\n{}\n}}",
+                    variants(&|icon| format!(
+                        "Self::{} => {}(style, filled, weight, grade, optical_size),",
+                        icon.idents.variant, icon.idents.try_function
+                    ))
+                )
+            });
+    }
+
+    if geometry {
+        name_impl
+            .new_fn("paths")
+            .doc("Returns the outline of every variant of this icon that was compiled in, in the same order as [`variants`](Self::variants).")
+            .vis("pub")
+            .arg_self()
+            .ret("&'static [&'static IconPath]")
+            .line(format!(
+                "match self {{\n{}\n}}",
+                variants(&|icon| format!("Self::{} => {},", icon.idents.variant, icon.paths_static))
+            ));
+
+        name_impl
+            .new_fn("try_path")
+            .doc("Returns the outline of the given variant of this icon, or an error if that variant wasn't compiled in.")
+            .vis("pub")
+            .arg_self()
+            .arg("style", "IconStyle")
+            .arg("filled", "bool")
+            .arg("weight", "u16")
+            .arg("grade", "i16")
+            .arg("optical_size", "u8")
+            .ret("Result<&'static IconPath, IconError>")
+            .line("let variant = IconVariant { style, filled, weight, grade, optical_size };")
+            .line("")
+            .line(
+                "match self.variants().iter().position(|available| *available == variant) {
+        Some(index) => Ok(self.paths()[index]),
+        None => Err(IconError::MissingVariant { name: self, variant }),
+    }",
+            );
+
+        name_impl
+            .new_fn("path")
+            .doc("Returns the outline of the given variant of this icon.\n\n# Panics\n\nPanics if that variant wasn't compiled in.")
+            .vis("pub")
+            .arg_self()
+            .arg("style", "IconStyle")
+            .arg("filled", "bool")
+            .arg("weight", "u16")
+            .arg("grade", "i16")
+            .arg("optical_size", "u8")
+            .ret("&'static IconPath")
+            .line("self.try_path(style, filled, weight, grade, optical_size).unwrap_or_else(|err| panic!(\"{err}\"))");
+
+        name_impl
+            .new_fn("metas")
+            .doc("Returns the layout information of every variant of this icon that was compiled in, in the same order as [`variants`](Self::variants).")
+            .vis("pub")
+            .arg_self()
+            .ret("&'static [&'static IconMeta]")
+            .line(format!(
+                "match self {{\n{}\n}}",
+                variants(&|icon| format!("Self::{} => {},", icon.idents.variant, icon.metas_static))
+            ));
+
+        name_impl
+            .new_fn("try_meta")
+            .doc("Returns the layout information of the given variant of this icon, or an error if that variant wasn't compiled in.")
+            .vis("pub")
+            .arg_self()
+            .arg("style", "IconStyle")
+            .arg("filled", "bool")
+            .arg("weight", "u16")
+            .arg("grade", "i16")
+            .arg("optical_size", "u8")
+            .ret("Result<&'static IconMeta, IconError>")
+            .line("let variant = IconVariant { style, filled, weight, grade, optical_size };")
+            .line("")
+            .line(
+                "match self.variants().iter().position(|available| *available == variant) {
+        Some(index) => Ok(self.metas()[index]),
+        None => Err(IconError::MissingVariant { name: self, variant }),
+    }",
+            );
+
+        name_impl
+            .new_fn("meta")
+            .doc("Returns the layout information of the given variant of this icon.\n\n# Panics\n\nPanics if that variant wasn't compiled in.")
+            .vis("pub")
+            .arg_self()
+            .arg("style", "IconStyle")
+            .arg("filled", "bool")
+            .arg("weight", "u16")
+            .arg("grade", "i16")
+            .arg("optical_size", "u8")
+            .ret("&'static IconMeta")
+            .line("self.try_meta(style, filled, weight, grade, optical_size).unwrap_or_else(|err| panic!(\"{err}\"))");
+    }
+
+    name_impl
+        .new_fn("masks")
+        .doc("Returns the masks of this icon rasterized while building, as set by `raster_sizes` in the icon config, along with the variant each belongs to.")
+        .vis("pub")
+        .arg_self()
+        .ret("&'static [(IconVariant, &'static AlphaMask<&'static [u8]>)]")
+        .line(format!(
+            "match self {{\n{}\n}}",
+            variants(&|icon| format!("Self::{} => {},", icon.idents.variant, icon.masks_static))
+        ));
+
+    name_impl
+        .new_fn("mask")
+        .doc("Returns the mask of the given variant of this icon rasterized while building at `size` by `size` pixels, if the icon config asked for it.")
+        .vis("pub")
+        .arg_self()
+        .arg("style", "IconStyle")
+        .arg("filled", "bool")
+        .arg("weight", "u16")
+        .arg("grade", "i16")
+        .arg("optical_size", "u8")
+        .arg("size", "u32")
+        .ret("Option<&'static AlphaMask<&'static [u8]>>")
+        .line("let variant = IconVariant { style, filled, weight, grade, optical_size };")
+        .line("")
+        .line(
+            "self.masks()
+    .iter()
+    .find(|(available, mask)| *available == variant && mask.width == size)
+    .map(|(_, mask)| *mask)",
+        );
+
+    name_impl
+        .new_fn("pngs")
+        .doc("Returns the PNG files of this icon encoded while building, as set by `png_sizes` in the icon config, along with the variant and size each belongs to.")
+        .vis("pub")
+        .arg_self()
+        .ret("&'static [(IconVariant, u32, &'static [u8])]")
+        .line(format!(
+            "match self {{\n{}\n}}",
+            variants(&|icon| format!("Self::{} => {},", icon.idents.variant, icon.pngs_static))
+        ));
+
+    name_impl
+        .new_fn("png")
+        .doc("Returns the PNG file of the given variant of this icon encoded while building at `size` by `size` pixels, if the icon config asked for it.")
+        .vis("pub")
+        .arg_self()
+        .arg("style", "IconStyle")
+        .arg("filled", "bool")
+        .arg("weight", "u16")
+        .arg("grade", "i16")
+        .arg("optical_size", "u8")
+        .arg("size", "u32")
+        .ret("Option<&'static [u8]>")
+        .line("let variant = IconVariant { style, filled, weight, grade, optical_size };")
+        .line("")
+        .line(
+            "self.pngs()
+    .iter()
+    .find(|(available, available_size, _)| *available == variant && *available_size == size)
+    .map(|(_, _, png)| *png)",
+        );
+
+    if svg {
+        name_impl
+            .new_fn("get")
+            .doc("Returns the SVG document of the given variant of this icon, if it was compiled in.")
+            .vis("pub")
+            .arg_self()
+            .arg("style", "IconStyle")
+            .arg("filled", "bool")
+            .arg("weight", "u16")
+            .arg("grade", "i16")
+            .arg("optical_size", "u8")
+            .ret("Option<&'static [u8]>")
+            .line("self.try_icon(style, filled, weight, grade, optical_size).ok()");
+
+        name_impl
+            .new_fn("icon")
+            .doc("Returns the SVG document of the given variant of this icon.\n\n# Panics\n\nPanics if that variant wasn't compiled in.")
+            .vis("pub")
+            .arg_self()
+            .arg("style", "IconStyle")
+            .arg("filled", "bool")
+            .arg("weight", "u16")
+            .arg("grade", "i16")
+            .arg("optical_size", "u8")
+            .ret("&'static [u8]")
+            .line("self.try_icon(style, filled, weight, grade, optical_size).unwrap_or_else(|err| panic!(\"{err}\"))");
+    }
+
+    root.new_impl("IconName")
+        .impl_trait("core::str::FromStr")
+        .associate_type("Err", "crate::ParseIconNameError")
+        .new_fn("from_str")
+        .arg("s", "&str")
+        .ret("Result<Self, Self::Err>")
+        .line(if icons.is_empty() {
+            "Err(crate::ParseIconNameError::new(s))".to_owned()
+        } else {
+            format!(
+                "match s {{\n{}\nvalue => Err(crate::ParseIconNameError::new(value)),\n}}",
+                variants(&|icon| {
+                    let names: Vec<String> = [&icon.name]
+                        .into_iter()
+                        .chain(&icon.aliases)
+                        .map(|name| format!("{name:?}"))
+                        .collect();
+
+                    format!(
+                        "{} => Ok(Self::{}),",
+                        names.join(" | "),
+                        icon.idents.variant
+                    )
+                })
+            )
+        });
+
+    root.new_impl("IconName")
+        .impl_trait("core::fmt::Display")
+        .new_fn("fmt")
+        .arg_ref_self()
+        .arg("f", "&mut core::fmt::Formatter<'_>")
+        .ret("core::fmt::Result")
+        .line("f.write_str(self.as_str())");
+}
+
+fn main() {
+    let out_dir = env::var("OUT_DIR").unwrap();
+    let bundled = bundled_icons();
+    let zero_config = !bundled.is_empty() || env::var_os("CARGO_FEATURE_MACROS").is_some();
+
+    println!("cargo:rerun-if-env-changed={CONFIG_ENV}");
+
+    let mut config_note = String::new();
+    let config = if cfg!(docsrs) {
+        if let Ok(source_dir) = env::var("SOURCE_DIR") {
+            let config_path = Path::new(&source_dir).join(CONFIG_FILE);
+
+            println!("cargo:rerun-if-changed={}", config_path.display());
+
+            load_icons(&ConfigSource::File(config_path)).unwrap_or_default()
+        } else {
+            Config::default()
+        }
+    } else {
+        let out_dir = Path::new(&out_dir).canonicalize().unwrap();
+
+        match find_config(&out_dir) {
+            Ok((source, from_pwd)) => {
+                eprintln!("Icon config: {source}");
+
+                // Only the shell sets $PWD, and it may well be stale, so a
+                // config found through it is worth telling about.
+                if from_pwd {
+                    config_note = format!(
+                        "// Icons from {source}, found above $PWD rather than the target \
+                         directory.\n"
+                    );
+                }
+
+                match &source {
+                    ConfigSource::File(path) | ConfigSource::Manifest { path, .. } => {
+                        println!("cargo:rerun-if-changed={}", path.display());
+                    }
+                }
+
+                load_icons(&source).unwrap_or_else(|err| {
+                    if from_pwd {
+                        fail(&[format!(
+                            "{err}\n(found above $PWD rather than the target directory)"
+                        )])
+                    }
+
+                    fail(&[err])
+                })
+            }
+            // Icons can be picked with `icon!()` or the `all-*` features alone,
+            // without any config.
+            Err(_) if zero_config => Config::default(),
+            Err(tried) => panic!(
+                "Couldn't find the icon config, tried:\n  {}\n\
+                 Set ${CONFIG_ENV} or `[package.metadata.{METADATA_KEY}] config` to its path, \
+                 or list the icons in `[package.metadata.{METADATA_KEY}] icons`",
+                tried.join("\n  ")
+            ),
+        }
+    };
+
+    // Sorted so that the generated code doesn't depend on the order of the
+    // config, keeping builds reproducible.
+    let mut icons: BTreeMap<String, Vec<(IconInfo, PathBuf)>> = BTreeMap::new();
+
+    let search_paths = config.search_paths;
+    let normalize = config.normalize;
+    let png_options = config.png;
+    let embed_svg = config.svg;
+    // Outlines and layout information add up with many icons, so they're only
+    // generated when asked for.
+    let geometry = env::var_os("CARGO_FEATURE_GEOMETRY").is_some();
+    let config = expand_patterns(config.icons, &search_paths);
+
+    if let Some(size) = normalize.filter(|size| !size.is_finite() || *size <= 0.0) {
+        fail(&[format!(
+            "Icons can't be normalized to a box of {size}, expected a positive size such as 24 or 1"
+        )]);
+    }
+
+    for dir in &search_paths {
+        println!("cargo:rerun-if-changed={}", dir.display());
+    }
+
+    let configured: HashSet<(String, String)> = config
+        .iter()
+        .map(|icon| (icon.name.clone(), icon.file_name()))
+        .collect();
+    let bundled = bundled
+        .into_iter()
+        .filter(|icon| !configured.contains(&(icon.name.clone(), icon.file_name())));
+
+    let shipped = Path::new(SHIPPED_ICONS_PATH);
+    let mut shadowed = HashSet::new();
+
+    let mut errors = Vec::new();
+    let mut available_names = None;
+
+    for icon in config.into_iter().chain(bundled) {
+        if let Err(err) = icon.validate() {
+            errors.push(err);
+            continue;
+        }
+
+        let icon = follow_rename(icon, &search_paths);
+
+        let path = match locate_icon(&icon, &search_paths) {
+            Ok(path) => path,
+            Err(tried) => {
+                let names = available_names.get_or_insert_with(|| icon_names(&search_paths));
+
+                errors.push(missing_icon_message(&icon, &search_paths, names, &tried));
+                continue;
+            }
+        };
+
+        if icon.path.is_some() {
+            println!("cargo:rerun-if-changed={}", path.display());
+        }
+
+        if !path.starts_with(shipped)
+            && shipped.join(&icon.name).join(icon.file_name()).is_file()
+            && shadowed.insert(icon.name.clone())
+        {
+            println!(
+                "cargo:warning=Custom icon {} at {} replaces the shipped icon of the same name",
+                icon.name,
+                path.display()
+            );
+        }
+
+        let variants = icons.entry(icon.name.clone()).or_default();
+        let file_name = icon.file_name();
+
+        match variants
+            .iter_mut()
+            .find(|(other, _)| other.file_name() == file_name)
+        {
+            // The same variant listed twice, e.g. once on its own and once
+            // through a pattern: the first entry wins.
+            Some((other, other_path)) => {
+                if *other_path != path {
+                    println!(
+                        "cargo:warning=Icon {} is configured more than once as {}, \
+                         using {} rather than {}",
+                        icon.name,
+                        file_name.trim_end_matches(".svg"),
+                        other_path.display(),
+                        path.display()
+                    );
+                }
+
+                // Like the sizes, a resize applies whichever entry sets it,
+                // but only one of them can.
+                match (other.resize, icon.resize) {
+                    (None, resize) => other.resize = resize,
+                    (Some(first), Some(resize)) if first != resize => println!(
+                        "cargo:warning=Icon {} is configured more than once as {} with different \
+                         `resize` values, resizing it to {}x{} with a padding of {} as first \
+                         configured",
+                        icon.name,
+                        file_name.trim_end_matches(".svg"),
+                        first.width,
+                        first.height,
+                        first.padding
+                    ),
+                    _ => {}
+                }
+
+                other.aliases.extend(icon.aliases);
+                other.raster_sizes.extend(icon.raster_sizes);
+                other.png_sizes.extend(icon.png_sizes);
+            }
+            None => variants.push((icon, path)),
+        }
+    }
+
+    for variants in icons.values_mut() {
+        variants.sort_by_key(|(info, _)| info.sort_key());
+
+        for (info, _) in variants {
+            info.raster_sizes.sort_unstable();
+            info.raster_sizes.dedup();
+            info.png_sizes.sort_unstable();
+            info.png_sizes.dedup();
+        }
+    }
+
+    if !errors.is_empty() {
+        fail(&errors);
+    }
+
+    let mut root = Scope::new();
+
+    push_icon_style(&mut root);
+
+    let mut generated = Vec::new();
+    let mut registry = IdentRegistry::default();
+
+    // Top level functions that icons called `path` or `meta` would otherwise
+    // generate.
+    registry.reserve("icon_path");
+    registry.reserve("try_icon_path");
+    registry.reserve("icon_meta");
+    registry.reserve("try_icon_meta");
+    registry.reserve("icon_mask");
+    registry.reserve("icon_png");
+
+    let configured_names: HashSet<String> = icons.keys().cloned().collect();
+
+    // Many variants are byte for byte the same file, e.g. the filled outlined
+    // and sharp variants of most icons: each distinct file is only included
+    // once, and every variant using it points at the same statics, named after
+    // the first variant using it.
+    let mut statics: HashMap<Vec<u8>, IconFile> = HashMap::new();
+    let mask_dir = Path::new(&out_dir).join("mask");
+    let png_dir = Path::new(&out_dir).join("png");
+    let svg_dir = Path::new(&out_dir).join("svg");
+
+    fs::create_dir_all(&mask_dir).unwrap();
+    fs::create_dir_all(&png_dir).unwrap();
+    fs::create_dir_all(&svg_dir).unwrap();
+
+    let mut shared_variants = 0;
+    let mut saved_bytes = 0;
+
+    for (name, variants) in icons {
+        let idents = registry.register(&name);
+        let mut match_variants = Vec::new();
+        let mut available = Vec::new();
+        let mut paths = Vec::new();
+        let mut metas = Vec::new();
+        let mut masks = Vec::new();
+        let mut pngs = Vec::new();
+        let mut aliases: Vec<String> = RENAMED_ICONS
+            .iter()
+            .filter(|(old, new)| *new == name && !configured_names.contains(*old))
+            .map(|(old, _)| old.to_string())
+            .collect();
+
+        for (info, _) in &variants {
+            aliases.extend(info.aliases.iter().cloned());
+        }
+
+        aliases.sort();
+        aliases.dedup();
+
+        for (info, path) in variants {
+            let mut contents = fs::read(&path)
+                .unwrap_or_else(|err| panic!("Couldn't read {}: {err}", path.display()));
+
+            if let Some(Resize {
+                width,
+                height,
+                padding,
+            }) = info.resize
+            {
+                contents = svg::resize(&contents, width, height, padding).into_bytes();
+            }
+
+            let file = match statics.entry(contents) {
+                Entry::Occupied(entry) => {
+                    shared_variants += 1;
+                    saved_bytes += entry.key().len();
+
+                    entry.into_mut()
+                }
+                Entry::Vacant(entry) => {
+                    let static_name = format!("{}_{}", idents.constant, info.const_suffix());
+
+                    registry.claim(&static_name, &name);
+
+                    let svg = String::from_utf8_lossy(entry.key());
+                    let (data, commands) = icon_path(&svg, normalize).unwrap_or_else(|err| {
+                        println!(
+                            "cargo:warning=Couldn't read the outline of icon {} at {}: {err}",
+                            name,
+                            path.display()
+                        );
+
+                        (String::new(), Vec::new())
+                    });
+
+                    if embed_svg {
+                        // Resized documents are written out to be included instead.
+                        let svg_path = if info.resize.is_some() {
+                            let svg_path =
+                                svg_dir.join(format!("{}.svg", static_name.to_ascii_lowercase()));
+
+                            fs::write(&svg_path, entry.key()).unwrap_or_else(|err| {
+                                panic!("Couldn't write {}: {err}", svg_path.display())
+                            });
+
+                            svg_path
+                        } else {
+                            path.canonicalize().unwrap()
+                        };
+
+                        root.raw(format!(
+                            "static {static_name}: &[u8] = include_bytes!(\"{}\");",
+                            svg_path.display()
+                        ));
+                    }
+
+                    // The box the commands are laid out in.
+                    let path_view_box = match normalize {
+                        Some(size) => [0.0, 0.0, size, size],
+                        None => svg::root(&svg)
+                            .and_then(|root| root.view_box())
+                            .unwrap_or_default(),
+                    };
+
+                    if geometry {
+                        registry.claim(&format!("{static_name}_PATH"), &name);
+                        registry.claim(&format!("{static_name}_META"), &name);
+
+                        // Coordinates such as 6.28 are just that, not approximations of τ.
+                        root.raw(format!(
+                            "#[allow(clippy::approx_constant)]\nstatic {static_name}_PATH: IconPath = IconPath {{ data: {data:?}, commands: &[{}], view_box: {:?} }};",
+                            commands
+                                .iter()
+                                .map(|command| format!("PathCommand::{command:?}"))
+                                .collect::<Vec<_>>()
+                                .join(", "),
+                            Rect::from(path_view_box)
+                        ));
+                        root.raw(format!(
+                            "#[allow(clippy::approx_constant)]\nstatic {static_name}_META: IconMeta = {:?};",
+                            icon_meta(&svg, &commands)
+                        ));
+                    }
+
+                    entry.insert(IconFile {
+                        static_name,
+                        commands,
+                        view_box: path_view_box,
+                        raster_sizes: HashSet::new(),
+                        png_sizes: HashSet::new(),
+                    })
+                }
+            };
+            let static_name = &file.static_name;
+
+            for &size in &info.raster_sizes {
+                let mask_static = format!("{static_name}_MASK_{size}");
+
+                if file.raster_sizes.insert(size) {
+                    registry.claim(&mask_static, &name);
+
+                    let data = raster::rasterize(&file.commands, file.view_box, size, size);
+                    // Written out rather than as an array literal, which large
+                    // masks would bloat the generated code with.
+                    let mask_path =
+                        mask_dir.join(format!("{}.bin", mask_static.to_ascii_lowercase()));
+
+                    fs::write(&mask_path, data).unwrap_or_else(|err| {
+                        panic!("Couldn't write {}: {err}", mask_path.display())
+                    });
+
+                    root.raw(format!(
+                        "static {mask_static}: AlphaMask<&[u8]> = AlphaMask {{ width: {size}, height: {size}, data: include_bytes!(\"{}\") }};",
+                        mask_path.display()
+                    ));
+                }
+
+                masks.push(format!("({}, &{mask_static})", info.variant_expr()));
+            }
+
+            for &size in &info.png_sizes {
+                let png_static = format!("{static_name}_PNG_{size}");
+
+                if file.png_sizes.insert(size) {
+                    registry.claim(&png_static, &name);
+
+                    let mask = AlphaMask {
+                        width: size,
+                        height: size,
+                        data: raster::rasterize(&file.commands, file.view_box, size, size),
+                    };
+                    let png_path = png_dir.join(format!("{}.png", png_static.to_ascii_lowercase()));
+
+                    fs::write(
+                        &png_path,
+                        mask.to_png(
+                            png_options.color.0,
+                            png_options.background.map(|color| color.0),
+                        ),
+                    )
+                    .unwrap_or_else(|err| panic!("Couldn't write {}: {err}", png_path.display()));
+
+                    root.raw(format!(
+                        "static {png_static}: &[u8] = include_bytes!(\"{}\");",
+                        png_path.display()
+                    ));
+                }
+
+                pngs.push(format!("({}, {size}, {png_static})", info.variant_expr()));
+            }
+
+            match_variants.push(format!("{} => Ok({static_name}),", info.pattern()));
+            available.push(info.variant_expr());
+            paths.push(format!("&{static_name}_PATH"));
+            metas.push(format!("&{static_name}_META"));
+        }
+
+        let paths_static = format!("{}_PATHS", idents.constant);
+        let metas_static = format!("{}_METAS", idents.constant);
+
+        if geometry {
+            registry.claim(&paths_static, &name);
+
+            root.raw(format!(
+                "static {paths_static}: &[&IconPath] = &[{}];",
+                paths.join(", ")
+            ));
+
+            registry.claim(&metas_static, &name);
+
+            root.raw(format!(
+                "static {metas_static}: &[&IconMeta] = &[{}];",
+                metas.join(", ")
+            ));
+        }
+
+        let masks_static = format!("{}_MASKS", idents.constant);
+
+        registry.claim(&masks_static, &name);
+
+        root.raw(format!(
+            "static {masks_static}: &[(IconVariant, &AlphaMask<&[u8]>)] = &[{}];",
+            masks.join(", ")
+        ));
+
+        let pngs_static = format!("{}_PNGS", idents.constant);
+
+        registry.claim(&pngs_static, &name);
+
+        root.raw(format!(
+            "static {pngs_static}: &[(IconVariant, u32, &[u8])] = &[{}];",
+            pngs.join(", ")
+        ));
+
+        let variants_const = format!("{}_VARIANTS", idents.constant);
+
+        registry.claim(&variants_const, &name);
+
+        root.raw(format!(
+            "const {variants_const}: &[IconVariant] = &[{}];",
+            available.join(", ")
+        ));
+
+        if embed_svg {
+            match_variants.push(format!(
+                "_ => Err(IconError::MissingVariant {{
+        name: IconName::{},
+        variant: IconVariant {{ style, filled, weight, grade, optical_size }},
+    }}),",
+                idents.variant
+            ));
+
+            root.new_fn(&idents.try_function)
+                .vis("pub")
+                .arg("style", "IconStyle")
+                .arg("filled", "bool")
+                .arg("weight", "u16")
+                .arg("grade", "i16")
+                .arg("optical_size", "u8")
+                .ret("Result<&'static [u8], IconError>")
+                .line(format!(
+                    "match (style, filled, weight, grade, optical_size) {{\n{}\n}}",
+                    match_variants.join("\n")
+                ));
+
+            root.new_fn(&idents.function)
+                .vis("pub")
+                .arg("style", "IconStyle")
+                .arg("filled", "bool")
+                .arg("weight", "u16")
+                .arg("grade", "i16")
+                .arg("optical_size", "u8")
+                .ret("&'static [u8]")
+                .line(format!(
+                    "{}(style, filled, weight, grade, optical_size).unwrap_or_else(|err| panic!(\"{{err}}\"))",
+                    idents.try_function
+                ));
+        }
+
+        root.new_fn(&idents.mask_function)
+            .vis("pub")
+            .arg("style", "IconStyle")
+            .arg("filled", "bool")
+            .arg("weight", "u16")
+            .arg("grade", "i16")
+            .arg("optical_size", "u8")
+            .arg("size", "u32")
+            .ret("Option<&'static AlphaMask<&'static [u8]>>")
+            .line(format!(
+                "IconName::{}.mask(style, filled, weight, grade, optical_size, size)",
+                idents.variant
+            ));
+
+        root.new_fn(&idents.png_function)
+            .vis("pub")
+            .arg("style", "IconStyle")
+            .arg("filled", "bool")
+            .arg("weight", "u16")
+            .arg("grade", "i16")
+            .arg("optical_size", "u8")
+            .arg("size", "u32")
+            .ret("Option<&'static [u8]>")
+            .line(format!(
+                "IconName::{}.png(style, filled, weight, grade, optical_size, size)",
+                idents.variant
+            ));
+
+        // Only the SVG functions have deprecated aliases.
+        if embed_svg {
+            for alias in &aliases {
+                let alias_idents = IconIdents::new(alias);
+
+                registry.claim(&alias_idents.function, &name);
+                registry.claim(&alias_idents.try_function, &name);
+
+                for (alias_fn, target, ret) in [
+                    (
+                        &alias_idents.try_function,
+                        &idents.try_function,
+                        "Result<&'static [u8], IconError>",
+                    ),
+                    (&alias_idents.function, &idents.function, "&'static [u8]"),
+                ] {
+                    root.new_fn(alias_fn)
+                        .vis("pub")
+                        .attr(&format!(
+                            "deprecated(note = \"{alias} is an alias of {name}, use `{target}` instead\")"
+                        ))
+                        .arg("style", "IconStyle")
+                        .arg("filled", "bool")
+                        .arg("weight", "u16")
+                        .arg("grade", "i16")
+                        .arg("optical_size", "u8")
+                        .ret(ret)
+                        .line(format!(
+                            "{target}(style, filled, weight, grade, optical_size)"
+                        ));
+                }
+            }
+        }
+
+        generated.push(GeneratedIcon {
+            name,
+            idents,
+            variants_const,
+            paths_static,
+            metas_static,
+            masks_static,
+            pngs_static,
+            aliases,
+        });
+    }
+
+    // Recorded in the generated file, where it can be looked up without
+    // building verbosely.
+    let summary = format!(
+        "{config_note}// {shared_variants} icon variants are identical to another one, \
+         sharing their files saved {saved_bytes} bytes.\n\n"
+    );
+
+    push_icon_name(&mut root, &generated, geometry, embed_svg);
+    push_icon_macro(&mut root);
+
+    if embed_svg {
+        root.new_fn("try_icon")
+            .vis("pub")
+            .arg("name", "impl AsRef<str>")
+            .arg("style", "IconStyle")
+            .arg("filled", "bool")
+            .arg("weight", "u16")
+            .arg("grade", "i16")
+            .arg("optical_size", "u8")
+            .ret("Result<&'static [u8], IconError>")
+            .line("name.as_ref().parse::<IconName>()?.try_icon(style, filled, weight, grade, optical_size)");
+    }
+
+    root.new_fn("has_variant")
+        .vis("pub")
+        .arg("name", "impl AsRef<str>")
+        .arg("style", "IconStyle")
+        .arg("filled", "bool")
+        .arg("weight", "u16")
+        .arg("grade", "i16")
+        .arg("optical_size", "u8")
+        .ret("bool")
+        .line(
+            "name.as_ref()
+    .parse::<IconName>()
+    .is_ok_and(|name| name.has_variant(style, filled, weight, grade, optical_size))",
+        );
+
+    if geometry {
+        root.new_fn("try_icon_path")
+            .vis("pub")
+            .arg("name", "impl AsRef<str>")
+            .arg("style", "IconStyle")
+            .arg("filled", "bool")
+            .arg("weight", "u16")
+            .arg("grade", "i16")
+            .arg("optical_size", "u8")
+            .ret("Result<&'static IconPath, IconError>")
+            .line("name.as_ref().parse::<IconName>()?.try_path(style, filled, weight, grade, optical_size)");
+
+        root.new_fn("icon_path")
+            .vis("pub")
+            .arg("name", "impl AsRef<str>")
+            .arg("style", "IconStyle")
+            .arg("filled", "bool")
+            .arg("weight", "u16")
+            .arg("grade", "i16")
+            .arg("optical_size", "u8")
+            .ret("&'static IconPath")
+            .line("try_icon_path(name, style, filled, weight, grade, optical_size).unwrap_or_else(|err| panic!(\"{err}\"))");
+
+        root.new_fn("try_icon_meta")
+            .vis("pub")
+            .arg("name", "impl AsRef<str>")
+            .arg("style", "IconStyle")
+            .arg("filled", "bool")
+            .arg("weight", "u16")
+            .arg("grade", "i16")
+            .arg("optical_size", "u8")
+            .ret("Result<&'static IconMeta, IconError>")
+            .line("name.as_ref().parse::<IconName>()?.try_meta(style, filled, weight, grade, optical_size)");
+
+        root.new_fn("icon_meta")
+            .vis("pub")
+            .arg("name", "impl AsRef<str>")
+            .arg("style", "IconStyle")
+            .arg("filled", "bool")
+            .arg("weight", "u16")
+            .arg("grade", "i16")
+            .arg("optical_size", "u8")
+            .ret("&'static IconMeta")
+            .line("try_icon_meta(name, style, filled, weight, grade, optical_size).unwrap_or_else(|err| panic!(\"{err}\"))");
+    }
+
+    root.new_fn("icon_mask")
+        .vis("pub")
+        .arg("name", "impl AsRef<str>")
+        .arg("style", "IconStyle")
+        .arg("filled", "bool")
+        .arg("weight", "u16")
+        .arg("grade", "i16")
+        .arg("optical_size", "u8")
+        .arg("size", "u32")
+        .ret("Option<&'static AlphaMask<&'static [u8]>>")
+        .line("name.as_ref().parse::<IconName>().ok()?.mask(style, filled, weight, grade, optical_size, size)");
+
+    root.new_fn("icon_png")
+        .vis("pub")
+        .arg("name", "impl AsRef<str>")
+        .arg("style", "IconStyle")
+        .arg("filled", "bool")
+        .arg("weight", "u16")
+        .arg("grade", "i16")
+        .arg("optical_size", "u8")
+        .arg("size", "u32")
+        .ret("Option<&'static [u8]>")
+        .line("name.as_ref().parse::<IconName>().ok()?.png(style, filled, weight, grade, optical_size, size)");
+
+    if embed_svg {
+        root.new_fn("icon")
+            .vis("pub")
+            .arg("name", "impl AsRef<str>")
+            .arg("style", "IconStyle")
+            .arg("filled", "bool")
+            .arg("weight", "u16")
+            .arg("grade", "i16")
+            .arg("optical_size", "u8")
+            .ret("&'static [u8]")
+            .line("try_icon(name, style, filled, weight, grade, optical_size).unwrap_or_else(|err| panic!(\"{err}\"))");
+    }
 
-    std::fs::write(Path::new(&out_dir).join(CONSTANTS_FILE), root.to_string()).unwrap();
+    std::fs::write(
+        Path::new(&out_dir).join(CONSTANTS_FILE),
+        summary + &root.to_string(),
+    )
+    .unwrap();
 }
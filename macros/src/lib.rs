@@ -0,0 +1,335 @@
+//! Procedural macros backing `material-icons`.
+//!
+//! These aren't meant to be used directly: `material_icons::icon!` forwards to
+//! them along with the location of the shipped icons.
+
+use std::{fs, path::Path};
+
+use proc_macro::{Delimiter, Group, Ident, Literal, Punct, Spacing, Span, TokenStream, TokenTree};
+
+#[path = "../../src/suggest.rs"]
+mod suggest;
+
+const STYLES: [&str; 3] = ["outlined", "rounded", "sharp"];
+
+const DEFAULT_WEIGHT: i64 = 400;
+const DEFAULT_GRADE: i64 = 0;
+const DEFAULT_OPTICAL_SIZE: i64 = 24;
+
+const WEIGHTS: [i64; 7] = [100, 200, 300, 400, 500, 600, 700];
+const GRADES: [i64; 3] = [-25, 0, 200];
+const OPTICAL_SIZES: [i64; 4] = [20, 24, 40, 48];
+
+struct Error {
+    span: Span,
+    message: String,
+}
+
+impl Error {
+    fn new(span: Span, message: impl Into<String>) -> Self {
+        Self {
+            span,
+            message: message.into(),
+        }
+    }
+
+    /// Expands to `::core::compile_error!("...")`, pointing at the offending tokens.
+    fn into_compile_error(self) -> TokenStream {
+        let span = self.span;
+        let mut message = Literal::string(&self.message);
+
+        message.set_span(span);
+
+        let tokens = [
+            TokenTree::Punct(Punct::new(':', Spacing::Joint)),
+            TokenTree::Punct(Punct::new(':', Spacing::Alone)),
+            TokenTree::Ident(Ident::new("core", span)),
+            TokenTree::Punct(Punct::new(':', Spacing::Joint)),
+            TokenTree::Punct(Punct::new(':', Spacing::Alone)),
+            TokenTree::Ident(Ident::new("compile_error", span)),
+            TokenTree::Punct(Punct::new('!', Spacing::Alone)),
+            TokenTree::Group(Group::new(
+                Delimiter::Brace,
+                TokenTree::Literal(message).into(),
+            )),
+        ];
+
+        tokens
+            .into_iter()
+            .map(|mut token| {
+                token.set_span(span);
+                token
+            })
+            .collect()
+    }
+}
+
+/// The icon variant requested by an invocation of `icon!`.
+struct Request {
+    name: String,
+    name_span: Span,
+    style: &'static str,
+    filled: bool,
+    weight: i64,
+    grade: i64,
+    optical_size: i64,
+}
+
+impl Request {
+    /// Name of the file holding the requested variant, following the same
+    /// scheme as the build script.
+    fn file_name(&self) -> String {
+        let mut stem = format!("{}{}", if self.filled { "filled-" } else { "" }, self.style);
+
+        if self.weight != DEFAULT_WEIGHT {
+            stem.push_str(&format!("-wght{}", self.weight));
+        }
+
+        if self.grade != DEFAULT_GRADE {
+            stem.push_str(&format!("-grad{}", self.grade).replace('-', "n"));
+        }
+
+        if self.optical_size != DEFAULT_OPTICAL_SIZE {
+            stem.push_str(&format!("-opsz{}", self.optical_size));
+        }
+
+        format!("{stem}.svg")
+    }
+}
+
+/// Expands to the SVG document of a shipped icon as `&'static [u8]`.
+///
+/// Invoked as `icon!("path/to/icons"; name, style, filled, weight = 300)`, where
+/// everything after the name is optional and may come in any order.
+#[proc_macro]
+pub fn icon(input: TokenStream) -> TokenStream {
+    expand(input).unwrap_or_else(Error::into_compile_error)
+}
+
+fn expand(input: TokenStream) -> Result<TokenStream, Error> {
+    let mut tokens = input.into_iter();
+
+    let dir = match tokens.next() {
+        Some(TokenTree::Literal(literal)) => parse_str(&literal)
+            .ok_or_else(|| Error::new(literal.span(), "expected the icons directory"))?,
+        token => return Err(unexpected(token, "the icons directory")),
+    };
+
+    match tokens.next() {
+        Some(TokenTree::Punct(punct)) if punct.as_char() == ';' => {}
+        token => return Err(unexpected(token, "`;`")),
+    }
+
+    let mut args = split_args(tokens.collect());
+
+    if args.is_empty() {
+        return Err(Error::new(Span::call_site(), "expected an icon name"));
+    }
+
+    let (name, name_span) = parse_name(&args.remove(0))?;
+    let mut request = Request {
+        name,
+        name_span,
+        style: STYLES[0],
+        filled: false,
+        weight: DEFAULT_WEIGHT,
+        grade: DEFAULT_GRADE,
+        optical_size: DEFAULT_OPTICAL_SIZE,
+    };
+
+    for arg in args {
+        parse_option(&arg, &mut request)?;
+    }
+
+    let dir = Path::new(&dir);
+    let icon_dir = dir.join(&request.name);
+
+    if !icon_dir.is_dir() {
+        return Err(Error::new(
+            request.name_span,
+            unknown_icon_message(dir, &request.name),
+        ));
+    }
+
+    let path = icon_dir.join(request.file_name());
+
+    if !path.is_file() {
+        return Err(Error::new(
+            request.name_span,
+            missing_variant_message(&icon_dir, &request),
+        ));
+    }
+
+    let path = TokenTree::Literal(Literal::string(&path.display().to_string()));
+
+    Ok(
+        format!("{{ const ICON: &[u8] = ::core::include_bytes!({path}); ICON }}")
+            .parse()
+            .expect("Generated tokens are valid"),
+    )
+}
+
+fn unexpected(token: Option<TokenTree>, expected: &str) -> Error {
+    match token {
+        Some(token) => Error::new(
+            token.span(),
+            format!("expected {expected}, found `{token}`"),
+        ),
+        None => Error::new(Span::call_site(), format!("expected {expected}")),
+    }
+}
+
+/// Splits the arguments on top level commas, dropping a trailing one.
+fn split_args(tokens: Vec<TokenTree>) -> Vec<Vec<TokenTree>> {
+    let mut args = vec![Vec::new()];
+
+    for token in tokens {
+        match token {
+            TokenTree::Punct(punct) if punct.as_char() == ',' => args.push(Vec::new()),
+            token => args.last_mut().unwrap().push(token),
+        }
+    }
+
+    if args.last().is_some_and(Vec::is_empty) {
+        args.pop();
+    }
+
+    args
+}
+
+/// Unquotes a string literal, as far as the escapes in paths go.
+fn parse_str(literal: &Literal) -> Option<String> {
+    let literal = literal.to_string();
+    let inner = literal.strip_prefix('"')?.strip_suffix('"')?;
+
+    Some(inner.replace("\\\\", "\\").replace("\\\"", "\""))
+}
+
+/// Parses an icon name, given either as an identifier (`home`, `r#move`), a
+/// number-like literal (`10k`, `3d_rotation`) or a string literal.
+fn parse_name(arg: &[TokenTree]) -> Result<(String, Span), Error> {
+    match arg {
+        [TokenTree::Ident(ident)] => {
+            let name = ident.to_string();
+            let name = name.strip_prefix("r#").unwrap_or(&name).to_owned();
+
+            Ok((name, ident.span()))
+        }
+        [TokenTree::Literal(literal)] => {
+            let name = parse_str(literal).unwrap_or_else(|| literal.to_string());
+
+            Ok((name, literal.span()))
+        }
+        [token, ..] => Err(Error::new(token.span(), "expected an icon name")),
+        [] => Err(Error::new(Span::call_site(), "expected an icon name")),
+    }
+}
+
+/// Parses a style, `filled` or one of the `axis = value` options.
+fn parse_option(arg: &[TokenTree], request: &mut Request) -> Result<(), Error> {
+    match arg {
+        [TokenTree::Ident(ident)] => {
+            let option = ident.to_string();
+
+            if option == "filled" {
+                request.filled = true;
+            } else if let Some(style) = STYLES.iter().find(|style| **style == option) {
+                request.style = style;
+            } else {
+                return Err(Error::new(
+                    ident.span(),
+                    format!("unknown option `{option}`, expected `filled` or one of {STYLES:?}"),
+                ));
+            }
+
+            Ok(())
+        }
+        [TokenTree::Ident(ident), TokenTree::Punct(eq), value @ ..] if eq.as_char() == '=' => {
+            let axis = ident.to_string();
+            let (field, allowed) = match axis.as_str() {
+                "weight" => (&mut request.weight, &WEIGHTS[..]),
+                "grade" => (&mut request.grade, &GRADES[..]),
+                "optical_size" => (&mut request.optical_size, &OPTICAL_SIZES[..]),
+                _ => {
+                    return Err(Error::new(
+                        ident.span(),
+                        format!(
+                            "unknown option `{axis}`, expected `weight`, `grade` or `optical_size`"
+                        ),
+                    ))
+                }
+            };
+
+            let number = match value {
+                [TokenTree::Literal(literal)] => literal.to_string().parse::<i64>().ok(),
+                [TokenTree::Punct(minus), TokenTree::Literal(literal)]
+                    if minus.as_char() == '-' =>
+                {
+                    literal
+                        .to_string()
+                        .parse::<i64>()
+                        .ok()
+                        .map(|number| -number)
+                }
+                _ => None,
+            };
+
+            match number {
+                Some(number) if allowed.contains(&number) => {
+                    *field = number;
+                    Ok(())
+                }
+                _ => Err(Error::new(
+                    ident.span(),
+                    format!("`{axis}` must be one of {allowed:?}"),
+                )),
+            }
+        }
+        [token, ..] => Err(Error::new(
+            token.span(),
+            "expected a style, `filled` or `axis = value`",
+        )),
+        [] => Err(Error::new(Span::call_site(), "unexpected empty argument")),
+    }
+}
+
+fn unknown_icon_message(dir: &Path, name: &str) -> String {
+    let names: Vec<String> = fs::read_dir(dir)
+        .into_iter()
+        .flatten()
+        .filter_map(Result::ok)
+        .map(|entry| entry.file_name().to_string_lossy().into_owned())
+        .collect();
+    let suggestions: Vec<String> = suggest::suggestions(name, names.iter().map(String::as_str))
+        .into_iter()
+        .map(|candidate| format!("`{candidate}`"))
+        .collect();
+
+    if suggestions.is_empty() {
+        format!("there is no icon called `{name}`")
+    } else {
+        format!(
+            "there is no icon called `{name}`, did you mean {}?",
+            suggestions.join(", ")
+        )
+    }
+}
+
+fn missing_variant_message(icon_dir: &Path, request: &Request) -> String {
+    let mut available: Vec<String> = fs::read_dir(icon_dir)
+        .into_iter()
+        .flatten()
+        .filter_map(Result::ok)
+        .map(|entry| entry.file_name().to_string_lossy().into_owned())
+        .filter_map(|file| file.strip_suffix(".svg").map(str::to_owned))
+        .collect();
+
+    available.sort();
+
+    format!(
+        "icon `{}` has no `{}` variant, available: {}",
+        request.name,
+        request.file_name().trim_end_matches(".svg"),
+        available.join(", ")
+    )
+}
@@ -0,0 +1,19 @@
+use material_icons::icon;
+
+#[test]
+fn test_icon_macro() {
+    let svg = icon!(home, rounded, filled);
+
+    assert_eq!(
+        svg,
+        include_bytes!("../../icons/home/filled-rounded.svg").as_slice()
+    );
+    assert_eq!(
+        icon!(10k),
+        include_bytes!("../../icons/10k/outlined.svg").as_slice()
+    );
+    assert_eq!(
+        icon!("move", sharp),
+        include_bytes!("../../icons/move/sharp.svg").as_slice()
+    );
+}
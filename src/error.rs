@@ -0,0 +1,151 @@
+use core::fmt;
+
+use crate::{IconName, IconVariant};
+
+/// Error returned when parsing a string that isn't the name of any configured
+/// icon into an [`IconName`](crate::IconName).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseIconNameError {
+    name: String,
+}
+
+impl ParseIconNameError {
+    pub(crate) fn new(name: impl Into<String>) -> Self {
+        Self { name: name.into() }
+    }
+
+    /// The name that was rejected.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+impl fmt::Display for ParseIconNameError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "there is no icon called {}", self.name)
+    }
+}
+
+impl std::error::Error for ParseIconNameError {}
+
+/// Error returned when parsing a string that isn't one of `outlined`, `rounded`
+/// or `sharp` into an [`IconStyle`](crate::IconStyle).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseIconStyleError {
+    style: String,
+}
+
+impl ParseIconStyleError {
+    pub(crate) fn new(style: impl Into<String>) -> Self {
+        Self {
+            style: style.into(),
+        }
+    }
+
+    /// The style that was rejected.
+    pub fn style(&self) -> &str {
+        &self.style
+    }
+}
+
+impl fmt::Display for ParseIconStyleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "unknown icon style {:?}, expected one of outlined, rounded or sharp",
+            self.style
+        )
+    }
+}
+
+impl std::error::Error for ParseIconStyleError {}
+
+/// Error returned when parsing a string that isn't a hex color, `rgb(...)` or
+/// `currentColor` into a [`Fill`](crate::Fill).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseFillError {
+    fill: String,
+}
+
+impl ParseFillError {
+    pub(crate) fn new(fill: impl Into<String>) -> Self {
+        Self { fill: fill.into() }
+    }
+
+    /// The fill that was rejected.
+    pub fn fill(&self) -> &str {
+        &self.fill
+    }
+}
+
+impl fmt::Display for ParseFillError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "invalid fill {:?}, expected a color such as #1f1f1f, rgb(31, 31, 31) or currentColor",
+            self.fill
+        )
+    }
+}
+
+impl std::error::Error for ParseFillError {}
+
+/// Error returned by the fallible icon lookups such as [`try_icon`](crate::try_icon).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum IconError {
+    /// No icon with that name was compiled in.
+    UnknownName(ParseIconNameError),
+    /// The icon exists, but the requested variant wasn't compiled in.
+    MissingVariant {
+        name: IconName,
+        variant: IconVariant,
+    },
+}
+
+impl IconError {
+    /// The variants that *are* available for the requested icon.
+    ///
+    /// Empty if the icon itself is unknown.
+    pub fn available(&self) -> &'static [IconVariant] {
+        match self {
+            Self::UnknownName(_) => &[],
+            Self::MissingVariant { name, .. } => name.variants(),
+        }
+    }
+}
+
+impl From<ParseIconNameError> for IconError {
+    fn from(value: ParseIconNameError) -> Self {
+        Self::UnknownName(value)
+    }
+}
+
+impl fmt::Display for IconError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnknownName(err) => err.fmt(f),
+            Self::MissingVariant { name, variant } => {
+                write!(f, "icon {name} has no {variant} variant, available: ")?;
+
+                for (i, variant) in self.available().iter().enumerate() {
+                    if i > 0 {
+                        f.write_str(", ")?;
+                    }
+
+                    variant.fmt(f)?;
+                }
+
+                Ok(())
+            }
+        }
+    }
+}
+
+impl std::error::Error for IconError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::UnknownName(err) => Some(err),
+            Self::MissingVariant { .. } => None,
+        }
+    }
+}
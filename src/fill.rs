@@ -0,0 +1,101 @@
+use core::{fmt, str::FromStr};
+
+use crate::{svg, ParseFillError};
+
+/// A color to fill an icon with, as written in SVG's `fill` attribute.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Fill {
+    /// An RGB color, parsed from `#rgb`, `#rrggbb` or `rgb(r, g, b)`.
+    Rgb(u8, u8, u8),
+    /// `currentColor`, the color of the text around the icon, e.g. when it's
+    /// inlined into HTML.
+    CurrentColor,
+}
+
+impl From<[u8; 3]> for Fill {
+    fn from([red, green, blue]: [u8; 3]) -> Self {
+        Self::Rgb(red, green, blue)
+    }
+}
+
+impl FromStr for Fill {
+    type Err = ParseFillError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let value = s.trim();
+
+        // CSS keywords and functions are case insensitive.
+        if value.eq_ignore_ascii_case("currentColor") {
+            return Ok(Self::CurrentColor);
+        }
+
+        if let Some(digits) = value.strip_prefix('#') {
+            // `from_str_radix` would take signs too.
+            let hex = digits.bytes().all(|byte| byte.is_ascii_hexdigit());
+            let channel = |digits: &str| u8::from_str_radix(digits, 16).ok();
+            let rgb = match digits.len() {
+                3 if hex => (0..3)
+                    .map(|i| channel(&digits[i..i + 1]).map(|value| value * 17))
+                    .collect::<Option<Vec<_>>>(),
+                6 if hex => (0..3)
+                    .map(|i| channel(&digits[i * 2..i * 2 + 2]))
+                    .collect::<Option<Vec<_>>>(),
+                _ => None,
+            };
+
+            if let Some(&[red, green, blue]) = rgb.as_deref() {
+                return Ok(Self::Rgb(red, green, blue));
+            }
+        } else if let Some(arguments) = value
+            .get(..4)
+            .filter(|function| function.eq_ignore_ascii_case("rgb("))
+            .and_then(|_| value[4..].strip_suffix(')'))
+        {
+            // Both the legacy `rgb(r, g, b)` and the modern `rgb(r g b)`.
+            let channels: Option<Vec<u8>> = arguments
+                .split(|c: char| c == ',' || c.is_ascii_whitespace())
+                .filter(|channel| !channel.is_empty())
+                .map(|channel| {
+                    channel
+                        .bytes()
+                        .all(|byte| byte.is_ascii_digit())
+                        .then(|| channel.parse().ok())
+                        .flatten()
+                })
+                .collect();
+
+            if let Some(&[red, green, blue]) = channels.as_deref() {
+                return Ok(Self::Rgb(red, green, blue));
+            }
+        }
+
+        Err(ParseFillError::new(s))
+    }
+}
+
+/// Formats the fill the way SVG's `fill` attribute takes it, e.g. `#1f1f1f`
+/// or `currentColor`.
+impl fmt::Display for Fill {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Rgb(red, green, blue) => write!(f, "#{red:02x}{green:02x}{blue:02x}"),
+            Self::CurrentColor => f.write_str("currentColor"),
+        }
+    }
+}
+
+/// Returns an SVG document, such as one returned by [`icon`](crate::icon()),
+/// filled with the given color and, if set, opacity from 0 to 1. A NaN opacity
+/// is left out, as if it weren't set.
+///
+/// The `fill` and `fill-opacity` attributes are set on the root `<svg>`
+/// element, so shapes that set their own fill, as custom icons may, keep it.
+pub fn recolor(svg: &[u8], fill: Fill, opacity: Option<f32>) -> String {
+    let mut attributes = vec![("fill", fill.to_string())];
+
+    if let Some(opacity) = opacity.filter(|opacity| !opacity.is_nan()) {
+        attributes.push(("fill-opacity", opacity.clamp(0.0, 1.0).to_string()));
+    }
+
+    svg::set_attributes(&String::from_utf8_lossy(svg), &attributes)
+}
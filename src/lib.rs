@@ -1,15 +1,452 @@
+mod error;
+mod fill;
+mod mask;
+mod meta;
+mod path;
+mod png;
+#[cfg(feature = "raster")]
+mod raster;
+mod svg;
+mod variant;
+
+pub use error::{IconError, ParseFillError, ParseIconNameError, ParseIconStyleError};
+pub use fill::{recolor, Fill};
+pub use mask::AlphaMask;
+pub use meta::{IconMeta, Rect};
+pub use path::{IconPath, PathCommand};
+pub use svg::resize;
+pub use variant::IconVariant;
+
+#[cfg(feature = "macros")]
+#[doc(hidden)]
+pub use material_icons_macros as __macros;
+
 include!(concat!(env!("OUT_DIR"), "/icons.rs"));
 
 #[cfg(test)]
 mod tests {
-    use crate::{icon_downloading, IconStyle};
+    use crate::{
+        has_variant, icon_check, icon_check_mask, icon_check_png, icon_downloading, icon_mask,
+        icon_png, recolor, resize, try_icon, AlphaMask, Fill, IconError, IconName, IconStyle,
+        IconVariant, ParseIconStyleError,
+    };
+    #[cfg(feature = "geometry")]
+    use crate::{icon_meta, icon_path, try_icon_meta, try_icon_path, PathCommand, Rect};
     use core::str;
+    use std::{fs, path::Path};
 
     #[test]
     fn test_icon() {
         println!(
             "{}",
-            str::from_utf8(icon_downloading(IconStyle::Outlined, 0, 400, 24)).unwrap()
+            str::from_utf8(icon_downloading(IconStyle::Rounded, true, 400, 0, 24)).unwrap()
         )
     }
+
+    #[test]
+    fn test_icon_name() {
+        for &name in IconName::ALL {
+            assert_eq!(name.as_str().parse(), Ok(name));
+            assert_eq!(name.to_string(), name.as_str());
+        }
+
+        assert_eq!("10k".parse(), Ok(IconName::Icon10k));
+        assert!("no_such_icon".parse::<IconName>().is_err());
+    }
+
+    #[test]
+    fn test_try_icon() {
+        assert!(try_icon("downloading", IconStyle::Rounded, true, 400, 0, 24).is_ok());
+        assert!(has_variant(
+            "downloading",
+            IconStyle::Rounded,
+            true,
+            400,
+            0,
+            24
+        ));
+        // The `all-*` features only bundle icons at the default weight.
+        assert!(!has_variant(
+            "downloading",
+            IconStyle::Rounded,
+            true,
+            700,
+            0,
+            24
+        ));
+
+        let err = try_icon("downloading", IconStyle::Sharp, false, 300, 0, 48).unwrap_err();
+
+        assert!(err
+            .available()
+            .contains(&IconVariant::new(IconStyle::Rounded, true)));
+
+        #[cfg(not(any(
+            feature = "all-outlined",
+            feature = "all-rounded",
+            feature = "all-sharp"
+        )))]
+        {
+            assert!(!has_variant(
+                "downloading",
+                IconStyle::Sharp,
+                true,
+                400,
+                0,
+                24
+            ));
+            assert_eq!(
+                err.available(),
+                &[IconVariant::new(IconStyle::Rounded, true)]
+            );
+            assert_eq!(
+                err.to_string(),
+                "icon downloading has no sharp weight 300 optical size 48 variant, \
+                 available: filled rounded"
+            );
+        }
+
+        assert!(matches!(
+            try_icon("no_such_icon", IconStyle::Outlined, false, 400, 0, 24),
+            Err(IconError::UnknownName(_))
+        ));
+    }
+
+    #[test]
+    fn test_icon_style() {
+        for style in IconStyle::ALL {
+            assert_eq!(style.as_str().parse(), Ok(style));
+            assert_eq!(style.to_string(), style.as_str());
+        }
+
+        assert_eq!(IconStyle::default(), IconStyle::Outlined);
+        assert_eq!(
+            "Rounded".parse::<IconStyle>(),
+            Err(ParseIconStyleError::new("Rounded"))
+        );
+    }
+
+    #[test]
+    #[allow(deprecated)]
+    fn test_aliases() {
+        assert_eq!("download_progress".parse(), Ok(IconName::Downloading));
+        assert_eq!("done".parse(), Ok(IconName::Check));
+        assert_eq!(IconName::Check.aliases(), &["done"]);
+        assert_eq!(
+            crate::icon_download_progress(IconStyle::Rounded, true, 400, 0, 24),
+            icon_downloading(IconStyle::Rounded, true, 400, 0, 24)
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "geometry")]
+    fn test_icon_path() {
+        let svg = str::from_utf8(icon_check(IconStyle::Outlined, false, 400, 0, 24)).unwrap();
+        let path = icon_path("check", IconStyle::Outlined, false, 400, 0, 24);
+
+        assert!(svg.contains(&format!("d=\"{}\"", path.data)));
+        assert_eq!(path.commands.len(), 8);
+        assert_eq!(
+            path.commands[..3],
+            [
+                PathCommand::MoveTo {
+                    x: 382.0,
+                    y: -240.0
+                },
+                PathCommand::LineTo {
+                    x: 154.0,
+                    y: -468.0
+                },
+                PathCommand::LineTo {
+                    x: 211.0,
+                    y: -525.0
+                },
+            ]
+        );
+        assert_eq!(path.commands.last(), Some(&PathCommand::Close));
+
+        // Smooth quadratic curves reflect the previous control point.
+        let commands = IconName::Icon10k
+            .path(IconStyle::Outlined, false, 400, 0, 24)
+            .commands;
+
+        assert_eq!(
+            commands[10..12],
+            [
+                PathCommand::Quad {
+                    x1: 497.0,
+                    y1: -360.0,
+                    x: 508.5,
+                    y: -371.5
+                },
+                PathCommand::Quad {
+                    x1: 520.0,
+                    y1: -383.0,
+                    x: 520.0,
+                    y: -400.0
+                },
+            ]
+        );
+
+        assert!(matches!(
+            try_icon_path("downloading", IconStyle::Sharp, false, 700, 0, 24),
+            Err(IconError::MissingVariant { .. })
+        ));
+    }
+
+    #[test]
+    #[cfg(feature = "geometry")]
+    fn test_icon_meta() {
+        let meta = icon_meta("check", IconStyle::Outlined, false, 400, 0, 24);
+
+        assert_eq!(
+            meta.view_box,
+            Rect {
+                x: 0.0,
+                y: -960.0,
+                width: 960.0,
+                height: 960.0
+            }
+        );
+        assert_eq!((meta.width, meta.height), (24.0, 24.0));
+        assert_eq!(
+            meta.bounding_box,
+            Rect {
+                x: 154.0,
+                y: -721.0,
+                width: 652.0,
+                height: 481.0
+            }
+        );
+
+        // The rounded corners of the arrow reach past its control points.
+        let meta = IconName::Downloading.meta(IconStyle::Rounded, true, 400, 0, 24);
+
+        assert!(meta.bounding_box.y > -880.0 && meta.bounding_box.y < -870.0);
+
+        assert!(try_icon_meta("check", IconStyle::Sharp, false, 700, 0, 24).is_err());
+    }
+
+    #[test]
+    #[cfg(feature = "raster")]
+    fn test_rasterize() {
+        let path = icon_path("check", IconStyle::Outlined, false, 400, 0, 24);
+        let mask = path.rasterize(48, 48);
+
+        assert_eq!(mask.data.len(), 48 * 48);
+
+        // The coverage adds up to the area of the check mark, from the shoelace
+        // formula over its corners, scaled from 960 units to 48 pixels.
+        let corners: Vec<(f32, f32)> = path
+            .commands
+            .iter()
+            .filter_map(|command| match *command {
+                PathCommand::MoveTo { x, y } | PathCommand::LineTo { x, y } => Some((x, y)),
+                _ => None,
+            })
+            .collect();
+        let area = corners
+            .windows(2)
+            .map(|pair| pair[0].0 * pair[1].1 - pair[1].0 * pair[0].1)
+            .sum::<f32>()
+            .abs()
+            / 2.0
+            / 400.0;
+        let coverage = mask.data.iter().map(|&alpha| f32::from(alpha)).sum::<f32>() / 255.0;
+
+        assert!((coverage - area).abs() < 1.0, "{coverage} != {area}");
+
+        // Outside the check mark, then well inside its stem.
+        assert_eq!(mask.get(2, 2), Some(0));
+        assert_eq!(mask.get(18, 31), Some(255));
+        assert_eq!(mask.get(48, 0), None);
+
+        let rgba = mask.to_rgba([255, 0, 0, 255]);
+
+        assert_eq!(rgba.len(), 48 * 48 * 4);
+        assert_eq!(rgba[(31 * 48 + 18) * 4..][..4], [255, 0, 0, 255]);
+    }
+
+    #[test]
+    fn test_icon_mask() {
+        let mask = icon_mask("check", IconStyle::Outlined, false, 400, 0, 24, 24).unwrap();
+
+        assert_eq!((mask.width, mask.height), (24, 24));
+        assert_eq!(mask.data.len(), 24 * 24);
+        assert_eq!(mask.get(1, 1), Some(0));
+        assert_eq!(mask.data.iter().max(), Some(&255));
+
+        #[cfg(feature = "raster")]
+        assert_eq!(
+            mask.data,
+            icon_path("check", IconStyle::Outlined, false, 400, 0, 24)
+                .rasterize(24, 24)
+                .data
+        );
+
+        assert_eq!(IconName::Check.masks().len(), 2);
+        assert_eq!(
+            icon_check_mask(IconStyle::Outlined, false, 400, 0, 24, 16).map(|mask| mask.width),
+            Some(16)
+        );
+        assert!(icon_check_mask(IconStyle::Outlined, false, 400, 0, 24, 48).is_none());
+        assert!(icon_mask("10k", IconStyle::Outlined, false, 400, 0, 24, 24).is_none());
+        assert!(icon_mask("nope", IconStyle::Outlined, false, 400, 0, 24, 24).is_none());
+    }
+
+    #[test]
+    fn test_icon_png() {
+        let png = icon_png("check", IconStyle::Outlined, false, 400, 0, 24, 32).unwrap();
+
+        assert_eq!(png[..8], *b"\x89PNG\r\n\x1a\n");
+        assert_eq!(png[12..16], *b"IHDR");
+        assert_eq!(png[16..24], [0, 0, 0, 32, 0, 0, 0, 32]);
+        // IEND is always the same, down to its checksum.
+        assert_eq!(
+            png[png.len() - 12..],
+            [0, 0, 0, 0, b'I', b'E', b'N', b'D', 0xae, 0x42, 0x60, 0x82]
+        );
+
+        #[cfg(feature = "raster")]
+        assert_eq!(
+            png,
+            icon_path("check", IconStyle::Outlined, false, 400, 0, 24).to_png(
+                32,
+                32,
+                [0, 0, 0, 255],
+                None
+            )
+        );
+
+        assert_eq!(
+            icon_check_png(IconStyle::Outlined, false, 400, 0, 24, 32),
+            Some(png)
+        );
+        assert!(icon_png("check", IconStyle::Outlined, false, 400, 0, 24, 24).is_none());
+    }
+
+    #[test]
+    fn test_mask_to_png() {
+        let mask = AlphaMask {
+            width: 2,
+            height: 1,
+            data: vec![0, 255],
+        };
+        let png = mask.to_png([255, 0, 0, 255], Some([0, 0, 255, 255]));
+
+        // Each row behind its filter type.
+        assert_eq!(idat(&png), [0, 0, 0, 255, 255, 255, 0, 0, 255]);
+
+        // Mostly transparent icons compress to a fraction of their pixels.
+        let data: Vec<u8> = (0..256 * 256).map(|i| (i % 256 / 64 * 85) as u8).collect();
+        let mask = AlphaMask {
+            width: 256,
+            height: 256,
+            data,
+        };
+        let png = mask.to_png([31, 31, 31, 255], None);
+        let rows: Vec<u8> = mask
+            .to_rgba([31, 31, 31, 255])
+            .chunks(256 * 4)
+            .flat_map(|row| [&[0], row].concat())
+            .collect();
+
+        assert_eq!(idat(&png), rows);
+        assert!(png.len() < rows.len() / 20);
+    }
+
+    /// Decompresses the pixel data of a PNG file.
+    fn idat(png: &[u8]) -> Vec<u8> {
+        let len = u32::from_be_bytes(png[33..37].try_into().unwrap()) as usize;
+
+        assert_eq!(png[37..41], *b"IDAT");
+
+        miniz_oxide::inflate::decompress_to_vec_zlib(&png[41..41 + len]).unwrap()
+    }
+
+    #[test]
+    fn test_recolor() {
+        assert_eq!("#FFF".parse(), Ok(Fill::Rgb(255, 255, 255)));
+        assert_eq!("#1f1f1f".parse(), Ok(Fill::Rgb(31, 31, 31)));
+        assert_eq!("rgb(31, 31, 31)".parse(), Ok(Fill::Rgb(31, 31, 31)));
+        assert_eq!("rgb(31 31 31)".parse(), Ok(Fill::Rgb(31, 31, 31)));
+        assert_eq!("currentcolor".parse(), Ok(Fill::CurrentColor));
+        assert!("#1f1f".parse::<Fill>().is_err());
+        assert!("rgb(256, 0, 0)".parse::<Fill>().is_err());
+        assert!("red".parse::<Fill>().is_err());
+        assert!("#+fffff".parse::<Fill>().is_err());
+        assert!("#+ff".parse::<Fill>().is_err());
+        assert!("rgb(+1, 0, 0)".parse::<Fill>().is_err());
+
+        let svg = recolor(
+            icon_check(IconStyle::Outlined, false, 400, 0, 24),
+            Fill::Rgb(31, 31, 31),
+            Some(0.5),
+        );
+
+        assert!(svg.starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\" height=\"24\""));
+        assert!(svg.contains(" fill=\"#1f1f1f\" fill-opacity=\"0.5\"><path d="));
+
+        // Fills already on the root element are replaced, and those of shapes
+        // left alone.
+        let svg = recolor(
+            br#"<svg fill='red' viewBox="0 0 24 24"><path fill="none" d="M0 0h24v24H0z"/></svg>"#,
+            Fill::CurrentColor,
+            None,
+        );
+
+        assert_eq!(
+            svg,
+            r#"<svg viewBox="0 0 24 24" fill="currentColor"><path fill="none" d="M0 0h24v24H0z"/></svg>"#
+        );
+        assert_eq!(
+            recolor(b"<svg></svg>", Fill::CurrentColor, Some(f32::NAN)),
+            r#"<svg fill="currentColor"></svg>"#
+        );
+    }
+
+    #[test]
+    fn test_resize() {
+        let svg = icon_check(IconStyle::Outlined, false, 400, 0, 24);
+        let original = str::from_utf8(svg).unwrap();
+
+        assert_eq!(
+            resize(svg, 48.0, 48.0, 0.0),
+            format!(
+                "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"48\" height=\"48\" \
+                 viewBox=\"0 -960 960 960\">{}",
+                &original[original.find("<path").unwrap()..]
+            )
+        );
+        assert!(resize(svg, 20.0, 20.0, 120.0).contains(" viewBox=\"-120 -1080 1200 1200\">"));
+
+        // Documents without a viewBox get one from their size.
+        assert_eq!(
+            resize(br#"<svg width="24px" height="24px"/>"#, 16.0, 16.0, 2.0),
+            r#"<svg width="16" height="16" viewBox="-2 -2 28 28"/>"#
+        );
+    }
+
+    #[test]
+    fn test_schema_lists_shipped_icons() {
+        let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+        let schema: serde_json::Value =
+            serde_json::from_str(&fs::read_to_string(root.join("icons.schema.json")).unwrap())
+                .unwrap();
+        let listed: Vec<&str> = schema["definitions"]["shipped_name"]["enum"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|name| name.as_str().unwrap())
+            .collect();
+
+        let mut shipped: Vec<String> = fs::read_dir(root.join("icons"))
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().into_string().unwrap())
+            .collect();
+
+        shipped.sort();
+
+        assert_eq!(listed, shipped);
+    }
 }
@@ -0,0 +1,88 @@
+use super::png;
+
+/// An icon rendered into 8-bit coverage values, one byte per pixel, row by row
+/// from the top left.
+///
+/// The pixels are either owned, as rasterized at runtime, or `&'static [u8]`,
+/// as rasterized at build time.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct AlphaMask<D = Vec<u8>> {
+    pub width: u32,
+    pub height: u32,
+    pub data: D,
+}
+
+impl<D: AsRef<[u8]>> AlphaMask<D> {
+    /// Returns the coverage of the pixel at the given position, from 0 for
+    /// none to 255 for full, or `None` if the position is out of bounds.
+    pub fn get(&self, x: u32, y: u32) -> Option<u8> {
+        if x >= self.width || y >= self.height {
+            return None;
+        }
+
+        self.data
+            .as_ref()
+            .get(y as usize * self.width as usize + x as usize)
+            .copied()
+    }
+
+    /// Colors the mask, returning RGBA pixels with straight alpha: every pixel
+    /// has the color's red, green and blue, and its alpha scaled by coverage.
+    pub fn to_rgba(&self, [red, green, blue, alpha]: [u8; 4]) -> Vec<u8> {
+        self.data
+            .as_ref()
+            .iter()
+            .flat_map(|&coverage| {
+                let alpha = (u16::from(alpha) * u16::from(coverage) + 127) / 255;
+
+                [red, green, blue, alpha as u8]
+            })
+            .collect()
+    }
+
+    /// Encodes the mask as an RGBA PNG file, colored as by
+    /// [`to_rgba`](Self::to_rgba) and composited over `background`, or left
+    /// transparent where the icon doesn't cover it if `background` is `None`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `data` doesn't hold exactly `width * height` pixels, which
+    /// masks built by this crate always do.
+    pub fn to_png(&self, color: [u8; 4], background: Option<[u8; 4]>) -> Vec<u8> {
+        let mut rgba = self.to_rgba(color);
+
+        if let Some(background) = background {
+            for pixel in rgba.chunks_exact_mut(4) {
+                let over = blend(pixel.try_into().unwrap(), background);
+
+                pixel.copy_from_slice(&over);
+            }
+        }
+
+        png::encode(self.width, self.height, &rgba)
+    }
+}
+
+/// Composites a pixel over another, both with straight alpha.
+fn blend(top: [u8; 4], bottom: [u8; 4]) -> [u8; 4] {
+    let top_alpha = f32::from(top[3]) / 255.0;
+    let bottom_alpha = f32::from(bottom[3]) / 255.0 * (1.0 - top_alpha);
+    let alpha = top_alpha + bottom_alpha;
+
+    if alpha == 0.0 {
+        return [0; 4];
+    }
+
+    let channel = |i: usize| {
+        let value = (f32::from(top[i]) * top_alpha + f32::from(bottom[i]) * bottom_alpha) / alpha;
+
+        value.round() as u8
+    };
+
+    [
+        channel(0),
+        channel(1),
+        channel(2),
+        (alpha * 255.0).round() as u8,
+    ]
+}
@@ -0,0 +1,24 @@
+/// An axis-aligned rectangle.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Rect {
+    pub x: f32,
+    pub y: f32,
+    pub width: f32,
+    pub height: f32,
+}
+
+/// Layout information about an icon variant, computed at build time.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct IconMeta {
+    /// The viewBox of the SVG document, `0 -960 960 960` for the shipped icons.
+    pub view_box: Rect,
+    /// The width the SVG document is drawn at by default, 24 for the shipped
+    /// icons.
+    pub width: f32,
+    /// The height the SVG document is drawn at by default, 24 for the shipped
+    /// icons.
+    pub height: f32,
+    /// The tight bounding box of the icon's ink, in the coordinates of its
+    /// [`IconPath`](crate::IconPath) commands.
+    pub bounding_box: Rect,
+}
@@ -0,0 +1,97 @@
+use crate::Rect;
+#[cfg(feature = "raster")]
+use crate::{raster, AlphaMask};
+
+/// A segment of an icon's outline.
+///
+/// Coordinates are absolute, in the units of the icon's viewBox: relative
+/// commands and the shorthands `H`, `V`, `S` and `T` of SVG path data are
+/// resolved at build time into the commands below. When the icon config sets
+/// `normalize`, they're in a box of that size with its origin at the top left
+/// instead, e.g. from 0 to 24 rather than the shipped icons' `0 -960 960 960`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PathCommand {
+    /// Starts a new subpath at the given point.
+    MoveTo { x: f32, y: f32 },
+    /// Draws a straight line to the given point.
+    LineTo { x: f32, y: f32 },
+    /// Draws a cubic Bézier curve to `(x, y)` with the control points
+    /// `(x1, y1)` and `(x2, y2)`.
+    Cubic {
+        x1: f32,
+        y1: f32,
+        x2: f32,
+        y2: f32,
+        x: f32,
+        y: f32,
+    },
+    /// Draws a quadratic Bézier curve to `(x, y)` with the control point
+    /// `(x1, y1)`.
+    Quad { x1: f32, y1: f32, x: f32, y: f32 },
+    /// Draws an elliptical arc to `(x, y)`, as described by the SVG `A` command.
+    Arc {
+        rx: f32,
+        ry: f32,
+        /// Rotation of the ellipse's x axis, in degrees.
+        x_axis_rotation: f32,
+        large_arc: bool,
+        sweep: bool,
+        x: f32,
+        y: f32,
+    },
+    /// Closes the current subpath with a straight line to its start.
+    Close,
+}
+
+/// The outline of an icon variant, both as SVG path data and parsed.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct IconPath {
+    /// The `d` attribute of the icon's `<path>`, as found in its SVG document,
+    /// or rewritten in absolute commands when the icon config sets `normalize`.
+    pub data: &'static str,
+    /// The outline as absolute commands.
+    pub commands: &'static [PathCommand],
+    /// The box the commands are laid out in: the viewBox of the SVG document,
+    /// or the box set by `normalize` in the icon config.
+    pub view_box: Rect,
+}
+
+#[cfg(feature = "raster")]
+impl IconPath {
+    /// Rasterizes the outline into a mask of the given size, stretching its
+    /// view box over the whole mask.
+    ///
+    /// Filling follows the non-zero rule and edges are anti-aliased by the
+    /// exact area each pixel covers.
+    pub fn rasterize(&self, width: u32, height: u32) -> AlphaMask {
+        let Rect {
+            x,
+            y,
+            width: view_width,
+            height: view_height,
+        } = self.view_box;
+
+        AlphaMask {
+            width,
+            height,
+            data: raster::rasterize(
+                self.commands,
+                [x, y, view_width, view_height],
+                width,
+                height,
+            ),
+        }
+    }
+
+    /// Rasterizes the outline into a PNG file of the given size, colored and
+    /// composited as by [`AlphaMask::to_png`].
+    pub fn to_png(
+        &self,
+        width: u32,
+        height: u32,
+        color: [u8; 4],
+        background: Option<[u8; 4]>,
+    ) -> Vec<u8> {
+        self.rasterize(width, height).to_png(color, background)
+    }
+}
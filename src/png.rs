@@ -0,0 +1,315 @@
+//! A minimal PNG encoder, also included by the build script to embed PNGs.
+//!
+//! Pixel data is compressed into a single deflate block with the fixed Huffman
+//! codes, which keeps the encoder small: icons are mostly runs of transparent
+//! pixels, which matching repeats alone takes care of.
+
+const SIGNATURE: [u8; 8] = [0x89, b'P', b'N', b'G', b'\r', b'\n', 0x1a, b'\n'];
+
+/// How far back deflate can refer to.
+const WINDOW: usize = 1 << 15;
+
+/// The shortest and longest repeats deflate can refer to.
+const MIN_MATCH: usize = 3;
+const MAX_MATCH: usize = 258;
+
+/// How many earlier positions are tried for a repeat, trading compression for
+/// speed.
+const MAX_CHAIN: usize = 128;
+
+const HASH_BITS: u32 = 15;
+
+/// The shortest length of each length code from 257 on, and its extra bits.
+const LENGTH_BASES: [u16; 29] = [
+    3, 4, 5, 6, 7, 8, 9, 10, 11, 13, 15, 17, 19, 23, 27, 31, 35, 43, 51, 59, 67, 83, 99, 115, 131,
+    163, 195, 227, 258,
+];
+const LENGTH_EXTRA_BITS: [u8; 29] = [
+    0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 1, 1, 2, 2, 2, 2, 3, 3, 3, 3, 4, 4, 4, 4, 5, 5, 5, 5, 0,
+];
+
+/// The shortest distance of each distance code, and its extra bits.
+const DISTANCE_BASES: [u16; 30] = [
+    1, 2, 3, 4, 5, 7, 9, 13, 17, 25, 33, 49, 65, 97, 129, 193, 257, 385, 513, 769, 1025, 1537,
+    2049, 3073, 4097, 6145, 8193, 12289, 16385, 24577,
+];
+const DISTANCE_EXTRA_BITS: [u8; 30] = [
+    0, 0, 0, 0, 1, 1, 2, 2, 3, 3, 4, 4, 5, 5, 6, 6, 7, 7, 8, 8, 9, 9, 10, 10, 11, 11, 12, 12, 13,
+    13,
+];
+
+/// Encodes 8-bit RGBA pixels with straight alpha, row by row from the top
+/// left, as a PNG file.
+///
+/// # Panics
+///
+/// Panics if `rgba` doesn't hold exactly `width * height` pixels.
+pub fn encode(width: u32, height: u32, rgba: &[u8]) -> Vec<u8> {
+    let row_len = width as usize * 4;
+
+    assert_eq!(
+        rgba.len(),
+        row_len * height as usize,
+        "expected {width}x{height} RGBA pixels"
+    );
+
+    // Every row starts with its filter type, 0 for none.
+    let mut raw = Vec::with_capacity((row_len + 1) * height as usize);
+
+    for row in rgba.chunks_exact(row_len.max(1)).take(height as usize) {
+        raw.push(0);
+        raw.extend_from_slice(row);
+    }
+
+    let mut header = Vec::with_capacity(13);
+
+    header.extend_from_slice(&width.to_be_bytes());
+    header.extend_from_slice(&height.to_be_bytes());
+    // 8 bits per channel, RGBA, then the only compression and filter methods
+    // and no interlacing.
+    header.extend_from_slice(&[8, 6, 0, 0, 0]);
+
+    let mut png = SIGNATURE.to_vec();
+
+    chunk(&mut png, b"IHDR", &header);
+    chunk(&mut png, b"IDAT", &zlib(&raw));
+    chunk(&mut png, b"IEND", &[]);
+
+    png
+}
+
+/// Appends a chunk, framed by its length and checksum.
+fn chunk(png: &mut Vec<u8>, kind: &[u8; 4], data: &[u8]) {
+    png.extend_from_slice(&(data.len() as u32).to_be_bytes());
+    png.extend_from_slice(kind);
+    png.extend_from_slice(data);
+
+    let crc = crc32(kind.iter().chain(data));
+
+    png.extend_from_slice(&crc.to_be_bytes());
+}
+
+/// Compresses `data` into a zlib stream.
+fn zlib(data: &[u8]) -> Vec<u8> {
+    let mut bits = BitWriter {
+        // Deflate with a 32K window, and the check bits making the header a
+        // multiple of 31.
+        bytes: vec![0x78, 0x01],
+        buffer: 0,
+        len: 0,
+    };
+
+    // The final block, with the fixed codes.
+    bits.write(0b011, 3);
+
+    let mut matcher = Matcher {
+        head: vec![usize::MAX; 1 << HASH_BITS],
+        previous: vec![usize::MAX; WINDOW],
+    };
+    let mut pos = 0;
+
+    while pos < data.len() {
+        let (length, distance) = matcher.longest_match(data, pos);
+
+        if length >= MIN_MATCH {
+            bits.length(length);
+            bits.distance(distance);
+
+            for pos in pos..pos + length {
+                matcher.insert(data, pos);
+            }
+
+            pos += length;
+        } else {
+            bits.symbol(u16::from(data[pos]));
+            matcher.insert(data, pos);
+            pos += 1;
+        }
+    }
+
+    // The end of the block.
+    bits.symbol(256);
+
+    let mut stream = bits.finish();
+
+    stream.extend_from_slice(&adler32(data).to_be_bytes());
+    stream
+}
+
+fn hash(key: &[u8]) -> usize {
+    let key = u32::from(key[0]) << 16 | u32::from(key[1]) << 8 | u32::from(key[2]);
+
+    (key.wrapping_mul(0x9e37_79b1) >> (32 - HASH_BITS)) as usize
+}
+
+/// Finds earlier repeats through chains of the positions whose next bytes have
+/// the same hash.
+struct Matcher {
+    /// The last position with each hash.
+    head: Vec<usize>,
+    /// The previous position with the same hash as each one in the window.
+    previous: Vec<usize>,
+}
+
+impl Matcher {
+    fn insert(&mut self, data: &[u8], pos: usize) {
+        if let Some(key) = data.get(pos..pos + MIN_MATCH) {
+            let hash = hash(key);
+
+            self.previous[pos % WINDOW] = self.head[hash];
+            self.head[hash] = pos;
+        }
+    }
+
+    /// Finds the longest earlier repeat of the bytes at `pos`, as its length
+    /// and distance.
+    fn longest_match(&self, data: &[u8], pos: usize) -> (usize, usize) {
+        let Some(key) = data.get(pos..pos + MIN_MATCH) else {
+            return (0, 0);
+        };
+        let max_length = (data.len() - pos).min(MAX_MATCH);
+        let mut best = (0, 0);
+        let mut candidate = self.head[hash(key)];
+
+        for _ in 0..MAX_CHAIN {
+            if candidate == usize::MAX || pos - candidate > WINDOW {
+                break;
+            }
+
+            let length = data[candidate..]
+                .iter()
+                .zip(&data[pos..pos + max_length])
+                .take_while(|(a, b)| a == b)
+                .count();
+
+            if length > best.0 {
+                best = (length, pos - candidate);
+
+                if length == max_length {
+                    break;
+                }
+            }
+
+            let next = self.previous[candidate % WINDOW];
+
+            // Entries older than the window have been overwritten by newer
+            // ones.
+            if next >= candidate {
+                break;
+            }
+
+            candidate = next;
+        }
+
+        best
+    }
+}
+
+/// Packs bits into bytes, least significant first as deflate does.
+struct BitWriter {
+    bytes: Vec<u8>,
+    buffer: u32,
+    len: u32,
+}
+
+impl BitWriter {
+    fn write(&mut self, value: u32, len: u32) {
+        self.buffer |= value << self.len;
+        self.len += len;
+
+        while self.len >= 8 {
+            self.bytes.push(self.buffer as u8);
+            self.buffer >>= 8;
+            self.len -= 8;
+        }
+    }
+
+    /// Writes a Huffman code, which deflate packs most significant bit first.
+    fn code(&mut self, code: u32, len: u32) {
+        self.write(code.reverse_bits() >> (32 - len), len);
+    }
+
+    /// Writes a literal, length or end of block symbol with its fixed code.
+    fn symbol(&mut self, symbol: u16) {
+        let symbol = u32::from(symbol);
+
+        match symbol {
+            0..=143 => self.code(0x30 + symbol, 8),
+            144..=255 => self.code(0x190 + symbol - 144, 9),
+            256..=279 => self.code(symbol - 256, 7),
+            _ => self.code(0xc0 + symbol - 280, 8),
+        }
+    }
+
+    fn length(&mut self, length: usize) {
+        let code = LENGTH_BASES
+            .iter()
+            .rposition(|&base| usize::from(base) <= length)
+            .unwrap();
+
+        self.symbol(257 + code as u16);
+        self.write(
+            (length - usize::from(LENGTH_BASES[code])) as u32,
+            u32::from(LENGTH_EXTRA_BITS[code]),
+        );
+    }
+
+    fn distance(&mut self, distance: usize) {
+        let code = DISTANCE_BASES
+            .iter()
+            .rposition(|&base| usize::from(base) <= distance)
+            .unwrap();
+
+        self.code(code as u32, 5);
+        self.write(
+            (distance - usize::from(DISTANCE_BASES[code])) as u32,
+            u32::from(DISTANCE_EXTRA_BITS[code]),
+        );
+    }
+
+    /// Pads the last byte with zeros.
+    fn finish(mut self) -> Vec<u8> {
+        if self.len > 0 {
+            self.bytes.push(self.buffer as u8);
+        }
+
+        self.bytes
+    }
+}
+
+fn crc32<'a>(bytes: impl IntoIterator<Item = &'a u8>) -> u32 {
+    let mut crc = !0u32;
+
+    for &byte in bytes {
+        crc ^= u32::from(byte);
+
+        for _ in 0..8 {
+            crc = if crc & 1 == 1 {
+                (crc >> 1) ^ 0xedb8_8320
+            } else {
+                crc >> 1
+            };
+        }
+    }
+
+    !crc
+}
+
+fn adler32(bytes: &[u8]) -> u32 {
+    const MODULUS: u32 = 65521;
+
+    let (mut a, mut b) = (1u32, 0u32);
+
+    // The largest run that can't overflow before taking the modulus.
+    for run in bytes.chunks(5552) {
+        for &byte in run {
+            a += u32::from(byte);
+            b += a;
+        }
+
+        a %= MODULUS;
+        b %= MODULUS;
+    }
+
+    (b << 16) | a
+}
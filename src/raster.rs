@@ -0,0 +1,407 @@
+//! The rasterizer behind `IconPath::rasterize`, also included by the build
+//! script to pre-rasterize icons. It only relies on `super::PathCommand`, which
+//! the build script mirrors.
+
+use core::f64::consts::TAU;
+
+use super::PathCommand;
+
+/// How far, in pixels, flattened curves may stray from the real ones.
+const TOLERANCE: f32 = 0.1;
+
+/// Rasterizes an outline into 8-bit coverage values of the given size,
+/// stretching `view_box` (`[x, y, width, height]`) over the whole of it.
+///
+/// Filling follows the non-zero rule and edges are anti-aliased by the exact
+/// area each pixel covers.
+pub fn rasterize(commands: &[PathCommand], view_box: [f32; 4], width: u32, height: u32) -> Vec<u8> {
+    let mut rasterizer = Rasterizer::new(width as usize, height as usize);
+    let [min_x, min_y, view_width, view_height] = view_box;
+
+    if view_width <= 0.0 || view_height <= 0.0 {
+        return rasterizer.finish();
+    }
+
+    let scale_x = width as f32 / view_width;
+    let scale_y = height as f32 / view_height;
+    let transform = |x: f32, y: f32| ((x - min_x) * scale_x, (y - min_y) * scale_y);
+    let mut current = (0.0, 0.0);
+    let mut start = (0.0, 0.0);
+
+    for command in commands {
+        match *command {
+            PathCommand::MoveTo { x, y } => {
+                // Fills close every subpath, whether or not it says so.
+                rasterizer.line(current, start);
+                current = transform(x, y);
+                start = current;
+            }
+            PathCommand::LineTo { x, y } => {
+                let to = transform(x, y);
+
+                rasterizer.line(current, to);
+                current = to;
+            }
+            PathCommand::Quad { x1, y1, x, y } => {
+                let control = transform(x1, y1);
+                let to = transform(x, y);
+
+                rasterizer.quad(current, control, to);
+                current = to;
+            }
+            PathCommand::Cubic {
+                x1,
+                y1,
+                x2,
+                y2,
+                x,
+                y,
+            } => {
+                let to = transform(x, y);
+
+                rasterizer.cubic(current, transform(x1, y1), transform(x2, y2), to);
+                current = to;
+            }
+            PathCommand::Arc {
+                rx,
+                ry,
+                x_axis_rotation,
+                large_arc,
+                sweep,
+                x,
+                y,
+            } => {
+                let to = transform(x, y);
+
+                rasterizer.arc(
+                    current,
+                    (rx * scale_x, ry * scale_y),
+                    x_axis_rotation,
+                    (large_arc, sweep),
+                    to,
+                );
+                current = to;
+            }
+            PathCommand::Close => {
+                rasterizer.line(current, start);
+                current = start;
+            }
+        }
+    }
+
+    rasterizer.line(current, start);
+    rasterizer.finish()
+}
+
+type Point = (f32, f32);
+
+/// Accumulates the signed area covered by the edges of an outline in each
+/// pixel, which summed along a row gives the winding number, or the coverage
+/// of pixels an edge goes through.
+struct Rasterizer {
+    width: usize,
+    height: usize,
+    /// Two extra cells per row take what lies right of the mask, so that rows
+    /// don't spill into one another.
+    stride: usize,
+    cells: Vec<f32>,
+}
+
+impl Rasterizer {
+    fn new(width: usize, height: usize) -> Self {
+        let stride = width + 2;
+
+        Self {
+            width,
+            height,
+            stride,
+            cells: vec![0.0; stride * height],
+        }
+    }
+
+    fn line(&mut self, from: Point, to: Point) {
+        // Horizontal clipping doesn't change the winding of anything inside
+        // the mask, so edges are simply clamped to it.
+        let clamp = |(x, y): Point| (x.clamp(0.0, self.width as f32), y);
+        let (from, to) = (clamp(from), clamp(to));
+
+        if from.1 == to.1 {
+            return;
+        }
+
+        let (direction, top, bottom) = if from.1 < to.1 {
+            (1.0, from, to)
+        } else {
+            (-1.0, to, from)
+        };
+        let dxdy = (bottom.0 - top.0) / (bottom.1 - top.1);
+        let mut x = top.0;
+
+        if top.1 < 0.0 {
+            x -= top.1 * dxdy;
+        }
+
+        let first_row = top.1.max(0.0) as usize;
+        let last_row = (bottom.1.ceil().max(0.0) as usize).min(self.height);
+
+        for row in first_row..last_row {
+            let cells = &mut self.cells[row * self.stride..(row + 1) * self.stride];
+            let dy = ((row + 1) as f32).min(bottom.1) - (row as f32).max(top.1);
+            let next_x = x + dxdy * dy;
+            let area = dy * direction;
+            let (left, right) = if x < next_x { (x, next_x) } else { (next_x, x) };
+            let left_floor = left.floor();
+            let left_cell = left_floor as usize;
+            let right_ceil = right.ceil();
+            let right_cell = right_ceil as usize;
+
+            if right_cell <= left_cell + 1 {
+                // The edge stays within a single pixel of this row.
+                let middle = 0.5 * (x + next_x) - left_floor;
+
+                cells[left_cell] += area - area * middle;
+                cells[left_cell + 1] += area * middle;
+            } else {
+                let inverse_width = (right - left).recip();
+                let left_fraction = left - left_floor;
+                let first = 0.5 * inverse_width * (1.0 - left_fraction) * (1.0 - left_fraction);
+                let right_fraction = right - right_ceil + 1.0;
+                let last = 0.5 * inverse_width * right_fraction * right_fraction;
+
+                cells[left_cell] += area * first;
+
+                if right_cell == left_cell + 2 {
+                    cells[left_cell + 1] += area * (1.0 - first - last);
+                } else {
+                    let second = inverse_width * (1.5 - left_fraction);
+
+                    cells[left_cell + 1] += area * (second - first);
+
+                    for cell in &mut cells[left_cell + 2..right_cell - 1] {
+                        *cell += area * inverse_width;
+                    }
+
+                    let before_last = second + (right_cell - left_cell - 3) as f32 * inverse_width;
+
+                    cells[right_cell - 1] += area * (1.0 - before_last - last);
+                }
+
+                cells[right_cell] += area * last;
+            }
+
+            x = next_x;
+        }
+    }
+
+    fn quad(&mut self, from: Point, control: Point, to: Point) {
+        let deviation = f32::hypot(
+            from.0 - 2.0 * control.0 + to.0,
+            from.1 - 2.0 * control.1 + to.1,
+        );
+        let segments = segments((deviation / (8.0 * TOLERANCE)).sqrt());
+        let mut previous = from;
+
+        for i in 1..=segments {
+            let t = i as f32 / segments as f32;
+            let s = 1.0 - t;
+            let point = (
+                s * s * from.0 + 2.0 * s * t * control.0 + t * t * to.0,
+                s * s * from.1 + 2.0 * s * t * control.1 + t * t * to.1,
+            );
+
+            self.line(previous, point);
+            previous = point;
+        }
+    }
+
+    fn cubic(&mut self, from: Point, control1: Point, control2: Point, to: Point) {
+        let deviation = f32::max(
+            f32::hypot(
+                from.0 - 2.0 * control1.0 + control2.0,
+                from.1 - 2.0 * control1.1 + control2.1,
+            ),
+            f32::hypot(
+                control1.0 - 2.0 * control2.0 + to.0,
+                control1.1 - 2.0 * control2.1 + to.1,
+            ),
+        );
+        let segments = segments((3.0 * deviation / (4.0 * TOLERANCE)).sqrt());
+        let mut previous = from;
+
+        for i in 1..=segments {
+            let t = i as f32 / segments as f32;
+            let s = 1.0 - t;
+            let at = |a: f32, b: f32, c: f32, d: f32| {
+                s * s * s * a + 3.0 * s * s * t * b + 3.0 * s * t * t * c + t * t * t * d
+            };
+            let point = (
+                at(from.0, control1.0, control2.0, to.0),
+                at(from.1, control1.1, control2.1, to.1),
+            );
+
+            self.line(previous, point);
+            previous = point;
+        }
+    }
+
+    /// Flattens an SVG arc.
+    fn arc(
+        &mut self,
+        from: Point,
+        radii: Point,
+        x_axis_rotation: f32,
+        flags: (bool, bool),
+        to: Point,
+    ) {
+        let widen = |(x, y): Point| (f64::from(x), f64::from(y));
+        let Some(arc) = CenterArc::new(
+            widen(from),
+            widen(radii),
+            f64::from(x_axis_rotation),
+            flags,
+            widen(to),
+        ) else {
+            self.line(from, to);
+            return;
+        };
+
+        // The angle between points keeping the chord within tolerance.
+        let (rx, ry) = arc.radii;
+        let step = 2.0 * (1.0 - f64::from(TOLERANCE) / rx.max(ry)).max(-1.0).acos();
+        let segments = segments((arc.delta.abs() / step) as f32);
+        let mut previous = from;
+
+        for i in 1..=segments {
+            let point = if i == segments {
+                to
+            } else {
+                let (x, y) = arc.point(arc.start + arc.delta * i as f64 / segments as f64);
+
+                (x as f32, y as f32)
+            };
+
+            self.line(previous, point);
+            previous = point;
+        }
+    }
+
+    /// Sums the cells along each row into coverage, clamping the winding
+    /// number to one as the non-zero rule does.
+    fn finish(self) -> Vec<u8> {
+        let mut coverage = Vec::with_capacity(self.width * self.height);
+
+        for row in self.cells.chunks(self.stride) {
+            let mut winding = 0.0;
+
+            for (x, cell) in row.iter().enumerate() {
+                winding += cell;
+
+                if x < self.width {
+                    coverage.push((winding.abs().min(1.0) * 255.0).round() as u8);
+                }
+            }
+        }
+
+        coverage
+    }
+}
+
+/// An SVG arc converted from its end points to its center, following the
+/// endpoint to center conversion of the SVG specification.
+///
+/// This is computed in f64 for the build script, which measures the bounds
+/// of arcs with it.
+pub struct CenterArc {
+    pub center: (f64, f64),
+    /// The radii, scaled up if they were too small to reach the end point.
+    pub radii: (f64, f64),
+    /// The sine and cosine of the rotation of the x axis.
+    pub rotation: (f64, f64),
+    /// The angle the arc starts at, before stretching and rotating.
+    pub start: f64,
+    /// How far the arc turns from `start`, negative when it turns through
+    /// decreasing angles.
+    pub delta: f64,
+}
+
+impl CenterArc {
+    /// Converts an arc, or returns `None` if it's degenerate, in which case
+    /// it's a straight line.
+    pub fn new(
+        from: (f64, f64),
+        (rx, ry): (f64, f64),
+        x_axis_rotation: f64,
+        (large_arc, sweep): (bool, bool),
+        to: (f64, f64),
+    ) -> Option<Self> {
+        let (mut rx, mut ry) = (rx.abs(), ry.abs());
+
+        if rx == 0.0 || ry == 0.0 || from == to {
+            return None;
+        }
+
+        let (sin, cos) = x_axis_rotation.to_radians().sin_cos();
+        let dx = (from.0 - to.0) / 2.0;
+        let dy = (from.1 - to.1) / 2.0;
+        let x1 = cos * dx + sin * dy;
+        let y1 = -sin * dx + cos * dy;
+
+        // Radii too small to reach the end point are scaled up until they do.
+        let lambda = (x1 * x1) / (rx * rx) + (y1 * y1) / (ry * ry);
+
+        if lambda > 1.0 {
+            rx *= lambda.sqrt();
+            ry *= lambda.sqrt();
+        }
+
+        let numerator = rx * rx * ry * ry - rx * rx * y1 * y1 - ry * ry * x1 * x1;
+        let denominator = rx * rx * y1 * y1 + ry * ry * x1 * x1;
+        let sign = if large_arc == sweep { -1.0 } else { 1.0 };
+        let coefficient = sign * (numerator / denominator).max(0.0).sqrt();
+        let cx1 = coefficient * rx * y1 / ry;
+        let cy1 = -coefficient * ry * x1 / rx;
+        let cx = cos * cx1 - sin * cy1 + (from.0 + to.0) / 2.0;
+        let cy = sin * cx1 + cos * cy1 + (from.1 + to.1) / 2.0;
+
+        let angle =
+            |ux: f64, uy: f64, vx: f64, vy: f64| (ux * vy - uy * vx).atan2(ux * vx + uy * vy);
+        let start = angle(1.0, 0.0, (x1 - cx1) / rx, (y1 - cy1) / ry);
+        let mut delta = angle(
+            (x1 - cx1) / rx,
+            (y1 - cy1) / ry,
+            (-x1 - cx1) / rx,
+            (-y1 - cy1) / ry,
+        );
+
+        if !sweep && delta > 0.0 {
+            delta -= TAU;
+        } else if sweep && delta < 0.0 {
+            delta += TAU;
+        }
+
+        Some(Self {
+            center: (cx, cy),
+            radii: (rx, ry),
+            rotation: (sin, cos),
+            start,
+            delta,
+        })
+    }
+
+    /// Returns the point of the ellipse at the given angle.
+    pub fn point(&self, theta: f64) -> (f64, f64) {
+        let (theta_sin, theta_cos) = theta.sin_cos();
+        let ((cx, cy), (rx, ry), (sin, cos)) = (self.center, self.radii, self.rotation);
+
+        (
+            cx + rx * theta_cos * cos - ry * theta_sin * sin,
+            cy + rx * theta_cos * sin + ry * theta_sin * cos,
+        )
+    }
+}
+
+/// Number of line segments to flatten a curve into, given how many it would
+/// ideally take.
+fn segments(ideal: f32) -> usize {
+    (ideal.ceil() as usize).clamp(1, 256)
+}
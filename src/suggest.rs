@@ -0,0 +1,45 @@
+//! Suggestions for misspelled icon names. This isn't part of the library: the
+//! build script and the macros crate both include it.
+
+/// How many similarly named icons to suggest for a misspelled one.
+pub const MAX_SUGGESTIONS: usize = 3;
+
+/// Returns up to [`MAX_SUGGESTIONS`] of `names` closest to `name`, if they're
+/// close enough to plausibly be what was meant.
+pub fn suggestions<'a>(name: &str, names: impl IntoIterator<Item = &'a str>) -> Vec<&'a str> {
+    let max_distance = name.len().max(3) / 3 + 1;
+    let mut candidates: Vec<(usize, &str)> = names
+        .into_iter()
+        .map(|candidate| (edit_distance(name, candidate), candidate))
+        .filter(|(distance, _)| *distance <= max_distance)
+        .collect();
+
+    candidates.sort();
+
+    candidates
+        .into_iter()
+        .take(MAX_SUGGESTIONS)
+        .map(|(_, candidate)| candidate)
+        .collect()
+}
+
+/// Levenshtein distance between two names.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+
+    for (i, a) in a.chars().enumerate() {
+        let mut previous = row[0];
+
+        row[0] = i + 1;
+
+        for (j, b) in b.iter().enumerate() {
+            let substitution = previous + usize::from(a != *b);
+
+            previous = row[j + 1];
+            row[j + 1] = substitution.min(row[j] + 1).min(previous + 1);
+        }
+    }
+
+    row[b.len()]
+}
@@ -0,0 +1,178 @@
+//! Rewriting the root element of SVG documents, also included by the build
+//! script to resize icons.
+
+use core::ops::Range;
+
+/// Returns an SVG document, such as one returned by [`icon`](crate::icon()),
+/// drawn at `width` by `height` rather than its own size, with `padding` added
+/// around its viewBox on every side, in the units of the viewBox.
+///
+/// The path data is left as is: only the `width`, `height` and `viewBox`
+/// attributes of the root `<svg>` element change. Documents without a viewBox
+/// get one from their original width and height, so that the drawing is
+/// scaled rather than cropped.
+pub fn resize(svg: &[u8], width: f32, height: f32, padding: f32) -> String {
+    let svg = String::from_utf8_lossy(svg);
+    let mut attributes = vec![("width", width.to_string()), ("height", height.to_string())];
+
+    if let Some([x, y, view_width, view_height]) = root(&svg).and_then(|root| root.view_box()) {
+        attributes.push((
+            "viewBox",
+            format!(
+                "{} {} {} {}",
+                x - padding,
+                y - padding,
+                view_width + 2.0 * padding,
+                view_height + 2.0 * padding
+            ),
+        ));
+    }
+
+    set_attributes(&svg, &attributes)
+}
+
+/// The start tag of an element.
+pub(crate) struct Tag<'a> {
+    attributes: Vec<Attribute<'a>>,
+    /// Where the attributes end, before any `/>` or `>`.
+    end: usize,
+}
+
+impl<'a> Tag<'a> {
+    /// Returns the value of an attribute, without quotes or entities decoded.
+    pub(crate) fn get(&self, name: &str) -> Option<&'a str> {
+        self.attributes
+            .iter()
+            .find(|attribute| attribute.name == name)
+            .map(|attribute| attribute.value)
+    }
+
+    /// Returns a length such as `width`, in pixels or without a unit.
+    pub(crate) fn length(&self, name: &str) -> Option<f32> {
+        self.get(name)?.trim().trim_end_matches("px").parse().ok()
+    }
+
+    /// Returns the viewBox as `[min_x, min_y, width, height]`, falling back to
+    /// the width and height.
+    pub(crate) fn view_box(&self) -> Option<[f32; 4]> {
+        match self.get("viewBox") {
+            Some(view_box) => {
+                let numbers: Vec<f32> = view_box
+                    .split(|c: char| c == ',' || c.is_ascii_whitespace())
+                    .filter(|number| !number.is_empty())
+                    .map(str::parse)
+                    .collect::<Result<_, _>>()
+                    .ok()?;
+
+                numbers.try_into().ok()
+            }
+            None => Some([0.0, 0.0, self.length("width")?, self.length("height")?]),
+        }
+    }
+}
+
+/// An attribute of a start tag.
+struct Attribute<'a> {
+    name: &'a str,
+    /// The value, without quotes or entities decoded.
+    value: &'a str,
+    /// Where the attribute is in the document, whitespace before it included.
+    span: Range<usize>,
+}
+
+/// Returns the start tag of the root `<svg>` element.
+pub(crate) fn root(svg: &str) -> Option<Tag<'_>> {
+    tags(svg, "svg").next()
+}
+
+/// Returns the start tags of every element called `name`, in order.
+pub(crate) fn tags<'a>(svg: &'a str, name: &str) -> impl Iterator<Item = Tag<'a>> {
+    let open = format!("<{name}");
+    let starts: Vec<usize> = svg
+        .match_indices(&open)
+        .map(|(start, _)| start + open.len())
+        .filter(|&start| {
+            svg[start..].starts_with(|c: char| c.is_ascii_whitespace() || c == '>' || c == '/')
+        })
+        .collect();
+
+    starts.into_iter().map(|start| tag(svg, start))
+}
+
+/// Parses the attributes of a start tag, from right after its name.
+fn tag(svg: &str, mut position: usize) -> Tag<'_> {
+    let mut attributes = Vec::new();
+
+    loop {
+        let rest = &svg[position..];
+        let trimmed = rest.trim_start();
+
+        if trimmed.is_empty() || trimmed.starts_with(['>', '/']) {
+            return Tag {
+                attributes,
+                end: position,
+            };
+        }
+
+        let name_len = trimmed
+            .find(|c: char| c == '=' || c.is_ascii_whitespace() || c == '>' || c == '/')
+            .unwrap_or(trimmed.len());
+        let mut end = name_len;
+        let mut value = "";
+
+        if let Some(after_equals) = trimmed[name_len..].trim_start().strip_prefix('=') {
+            let after_equals = after_equals.trim_start();
+            let value_start = trimmed.len() - after_equals.len();
+
+            (value, end) = match after_equals.chars().next() {
+                Some(quote @ ('"' | '\'')) => match after_equals[1..].find(quote) {
+                    Some(len) => (&after_equals[1..len + 1], value_start + len + 2),
+                    None => (&after_equals[1..], trimmed.len()),
+                },
+                _ => {
+                    let len = after_equals
+                        .find(|c: char| c.is_ascii_whitespace() || c == '>')
+                        .unwrap_or(after_equals.len());
+
+                    (&after_equals[..len], value_start + len)
+                }
+            };
+        }
+
+        let len = rest.len() - trimmed.len() + end;
+
+        attributes.push(Attribute {
+            name: &trimmed[..name_len],
+            value,
+            span: position..position + len,
+        });
+        position += len;
+    }
+}
+
+/// Sets attributes of the root `<svg>` element, replacing those it already
+/// has and leaving the rest of the document as is. Documents without an
+/// `<svg>` element are returned unchanged.
+pub(crate) fn set_attributes(svg: &str, attributes: &[(&str, String)]) -> String {
+    let Some(root) = root(svg) else {
+        return svg.to_owned();
+    };
+    let mut output = String::with_capacity(svg.len() + 64);
+    let mut position = 0;
+
+    for attribute in root.attributes {
+        if attributes.iter().any(|(name, _)| *name == attribute.name) {
+            output.push_str(&svg[position..attribute.span.start]);
+            position = attribute.span.end;
+        }
+    }
+
+    output.push_str(&svg[position..root.end]);
+
+    for (name, value) in attributes {
+        output.push_str(&format!(" {name}=\"{value}\""));
+    }
+
+    output.push_str(&svg[root.end..]);
+    output
+}
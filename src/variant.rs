@@ -0,0 +1,66 @@
+use core::fmt;
+
+use crate::IconStyle;
+
+/// A single variant of an icon, as selected by the axes of Material Symbols.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct IconVariant {
+    pub style: IconStyle,
+    pub filled: bool,
+    /// Stroke weight, one of 100, 200, ..., 700.
+    pub weight: u16,
+    /// Grade, one of -25, 0 or 200.
+    pub grade: i16,
+    /// Optical size in dp, one of 20, 24, 40 or 48.
+    pub optical_size: u8,
+}
+
+impl IconVariant {
+    pub const DEFAULT_WEIGHT: u16 = 400;
+    pub const DEFAULT_GRADE: i16 = 0;
+    pub const DEFAULT_OPTICAL_SIZE: u8 = 24;
+
+    /// The variant of the given style and fill with every other axis at its
+    /// default, which is what the shipped icons are drawn with.
+    pub const fn new(style: IconStyle, filled: bool) -> Self {
+        Self {
+            style,
+            filled,
+            weight: Self::DEFAULT_WEIGHT,
+            grade: Self::DEFAULT_GRADE,
+            optical_size: Self::DEFAULT_OPTICAL_SIZE,
+        }
+    }
+}
+
+impl Default for IconVariant {
+    fn default() -> Self {
+        Self::new(IconStyle::default(), false)
+    }
+}
+
+/// Formats the variant the way icons.json spells it, e.g. `filled rounded` or
+/// `outlined weight 300 optical size 48`, leaving out axes at their default.
+impl fmt::Display for IconVariant {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.filled {
+            f.write_str("filled ")?;
+        }
+
+        self.style.fmt(f)?;
+
+        if self.weight != Self::DEFAULT_WEIGHT {
+            write!(f, " weight {}", self.weight)?;
+        }
+
+        if self.grade != Self::DEFAULT_GRADE {
+            write!(f, " grade {}", self.grade)?;
+        }
+
+        if self.optical_size != Self::DEFAULT_OPTICAL_SIZE {
+            write!(f, " optical size {}", self.optical_size)?;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,520 @@
+use std::{
+    env, fs,
+    hash::{DefaultHasher, Hash, Hasher},
+    path::{Path, PathBuf},
+    process::Command,
+    sync::Mutex,
+};
+
+/// Builds share a target directory, and so the file the code is generated in.
+static BUILD: Mutex<()> = Mutex::new(());
+
+/// Builds the crate with the given icon config and returns the generated code,
+/// failing if the build has warnings. The outlines and layout information are
+/// generated too, for the tests to check.
+fn generate(name: &str, config: &str) -> String {
+    let (generated, stderr) = generate_with_warnings(name, config);
+
+    assert!(
+        !stderr.contains("warning"),
+        "Building with the {name} config warned:\n{stderr}"
+    );
+
+    generated
+}
+
+/// Builds the crate like [`generate`], returning what it printed along with
+/// the generated code.
+fn generate_with_warnings(name: &str, config: &str) -> (String, String) {
+    let _build = BUILD.lock().unwrap_or_else(|err| err.into_inner());
+    let dir = Path::new(env!("CARGO_TARGET_TMPDIR")).join("codegen");
+    // Named after the features, for builds with other features not to be
+    // mistaken for this one.
+    let target_dir = dir.join("target-geometry");
+    let config_path = dir.join(format!("{name}.json"));
+
+    fs::create_dir_all(&dir).unwrap();
+    fs::write(&config_path, config).unwrap();
+
+    let output = build(&target_dir)
+        .args(["--features", "geometry"])
+        .env("MATERIAL_ICONS_CONFIG", &config_path)
+        .output()
+        .expect("Couldn't run cargo");
+    let stderr = String::from_utf8_lossy(&output.stderr);
+
+    assert!(
+        output.status.success(),
+        "Building with {} failed:\n{stderr}",
+        config_path.display()
+    );
+
+    (generated(&target_dir), stderr.into_owned())
+}
+
+/// Builds the crate without pointing it at a config, from `invoked_from` as
+/// cargo is when run in a consumer's project, and returns the generated code
+/// or the errors of the build.
+fn discover(target_dir: &Path, invoked_from: &Path) -> Result<String, String> {
+    let output = build(target_dir)
+        .env_remove("MATERIAL_ICONS_CONFIG")
+        .current_dir(invoked_from)
+        .env("PWD", invoked_from)
+        .output()
+        .expect("Couldn't run cargo");
+
+    if output.status.success() {
+        Ok(generated(target_dir))
+    } else {
+        Err(String::from_utf8_lossy(&output.stderr).into_owned())
+    }
+}
+
+/// A command building the library into `target_dir`.
+fn build(target_dir: &Path) -> Command {
+    let mut command = Command::new(env!("CARGO"));
+
+    command
+        // Not quiet, which would hide the warnings of the build script.
+        .args(["build", "--package", "material-icons", "--lib"])
+        .arg("--manifest-path")
+        .arg(Path::new(env!("CARGO_MANIFEST_DIR")).join("Cargo.toml"))
+        .env("CARGO_TARGET_DIR", target_dir);
+    command
+}
+
+/// Reads the code generated by the only build in `target_dir`.
+fn generated(target_dir: &Path) -> String {
+    let generated: Vec<PathBuf> = fs::read_dir(target_dir.join("debug/build"))
+        .unwrap()
+        .map(|entry| entry.unwrap().path().join("out/icons.rs"))
+        .filter(|path| path.is_file())
+        .collect();
+
+    assert_eq!(generated.len(), 1, "Expected a single build of the crate");
+
+    fs::read_to_string(&generated[0]).unwrap()
+}
+
+/// Reads the file a generated static includes.
+fn included(output: &str, static_name: &str) -> Vec<u8> {
+    let item = &output[output
+        .find(&format!("static {static_name}:"))
+        .unwrap_or_else(|| panic!("No {static_name} static"))..];
+    let path = &item[item.find("include_bytes!(\"").unwrap() + "include_bytes!(\"".len()..];
+
+    fs::read(&path[..path.find('"').unwrap()]).unwrap()
+}
+
+/// Writes files under `dir`, given as paths relative to it and contents,
+/// starting from scratch.
+fn write_project(dir: &Path, files: &[(&str, &str)]) {
+    // Only the project itself, the build is kept for the next run.
+    if let Ok(entries) = fs::read_dir(dir) {
+        for entry in entries {
+            let path = entry.unwrap().path();
+
+            if path.file_name() != Some("target".as_ref()) {
+                if path.is_dir() {
+                    fs::remove_dir_all(path).unwrap();
+                } else {
+                    fs::remove_file(path).unwrap();
+                }
+            }
+        }
+    }
+
+    for (path, contents) in files {
+        let path = dir.join(path);
+
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, contents).unwrap();
+    }
+}
+
+/// A consumer's project in a directory of its own, the build of the crate
+/// ending up in its `target` directory.
+fn project(name: &str, files: &[(&str, &str)]) -> PathBuf {
+    let dir = Path::new(env!("CARGO_TARGET_TMPDIR"))
+        .join("discovery")
+        .join(name);
+
+    write_project(&dir, files);
+    dir
+}
+
+#[test]
+fn test_reproducible_output() {
+    // The same icons, listed in different orders and with repeated entries.
+    let configs = [
+        r#"["arrow_*", "home", { "name": "home", "style": "all", "filled": "both" }, "check"]"#,
+        r#"["check", { "name": "home", "style": ["sharp", "rounded", "outlined"], "filled": "both" }, "arrow_*", "arrow_back", "home"]"#,
+    ];
+    let outputs: Vec<String> = configs
+        .iter()
+        .enumerate()
+        .map(|(i, config)| generate(&format!("reproducible-{i}"), config))
+        .collect();
+
+    assert!(outputs[0].contains("ICON_ARROW_BACK_OUTLINED"));
+
+    // The sharp variants of home are the same files as the outlined ones.
+    assert_eq!(outputs[0].matches("Ok(ICON_HOME_OUTLINED)").count(), 2);
+    assert_eq!(
+        outputs[0].matches("Ok(ICON_HOME_FILLED_OUTLINED)").count(),
+        2
+    );
+    assert!(!outputs[0].contains("ICON_HOME_SHARP"));
+    assert!(outputs[0].starts_with("// 2 icon variants are identical to another one"));
+    assert!(
+        outputs[0] == outputs[1],
+        "Generated code differs between builds"
+    );
+}
+
+#[test]
+fn test_normalize() {
+    let output = generate("normalize", r#"{ "normalize": 24, "icons": ["check"] }"#);
+
+    // `M382-240 154-468` in the shipped `0 -960 960 960` viewBox.
+    assert!(output.contains(
+        "PathCommand::MoveTo { x: 9.55, y: 18.0 }, PathCommand::LineTo { x: 3.85, y: 12.3 }"
+    ));
+    assert!(output.contains(r#"data: "M9.55 18 L3.85 12.3"#));
+}
+
+#[test]
+fn test_resize() {
+    let output = generate(
+        "resize",
+        r#"[{ "name": "check", "resize": { "width": 48, "height": 48, "padding": 96 } }]"#,
+    );
+
+    // The document is written out resized, and its layout follows.
+    assert!(output.contains("/svg/icon_check_outlined.svg"));
+    assert!(output.contains(
+        "view_box: Rect { x: -96.0, y: -1056.0, width: 1152.0, height: 1152.0 }, width: 48.0, height: 48.0"
+    ));
+}
+
+#[test]
+fn test_repeated_resize() {
+    // A resize set by any entry of a variant applies.
+    let output = generate(
+        "repeated-resize",
+        r#"["check", { "name": "check", "resize": { "width": 48, "height": 48 } }]"#,
+    );
+
+    assert!(output.contains("/svg/icon_check_outlined.svg"));
+
+    let (output, stderr) = generate_with_warnings(
+        "conflicting-resize",
+        r#"[
+            { "name": "check", "resize": { "width": 48, "height": 48 } },
+            { "name": "check", "resize": { "width": 20, "height": 20 } }
+        ]"#,
+    );
+
+    assert!(output.contains("width: 48.0, height: 48.0"));
+    assert!(
+        stderr.contains("Icon check is configured more than once as outlined with different `resize` values, resizing it to 48x48"),
+        "Expected a warning about the conflicting resize:\n{stderr}"
+    );
+}
+
+#[test]
+fn test_empty_config() {
+    // E.g. when only the macros are used, with no config at all.
+    let output = generate("empty", "[]");
+
+    assert!(output.contains("pub enum IconName {\n}"));
+    assert!(output.contains("Err(crate::ParseIconNameError::new(s))"));
+}
+
+#[test]
+fn test_png_only() {
+    let output = generate(
+        "png-only",
+        r#"{ "svg": false, "icons": [{ "name": "check", "png_sizes": [16], "alias": "tick" }] }"#,
+    );
+
+    assert!(output.contains("static ICON_CHECK_OUTLINED_PNG_16: &[u8] = include_bytes!("));
+    assert!(!output.contains(".svg\")"));
+    assert!(!output.contains("fn icon_check("));
+    assert!(!output.contains("fn try_icon("));
+    assert!(output.contains("fn icon_check_png("));
+}
+
+#[test]
+fn test_out_of_range_path() {
+    let dir = Path::new(env!("CARGO_TARGET_TMPDIR")).join("codegen");
+
+    fs::create_dir_all(&dir).unwrap();
+    fs::write(
+        dir.join("huge.svg"),
+        r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 24 24"><path d="M0 0L1e39 0z"/></svg>"#,
+    )
+    .unwrap();
+
+    let (output, stderr) = generate_with_warnings(
+        "out-of-range",
+        r#"[{ "name": "huge", "path": "huge.svg" }]"#,
+    );
+
+    // The outline is left empty rather than failing the build.
+    assert!(
+        stderr.contains("Couldn't read the outline of icon huge")
+            && stderr.contains("number out of range at offset 5"),
+        "Expected a warning about the outline:\n{stderr}"
+    );
+    assert!(output.contains("data: \"\", commands: &[]"));
+}
+
+#[test]
+fn test_unfilled_paths() {
+    let dir = Path::new(env!("CARGO_TARGET_TMPDIR")).join("codegen");
+
+    fs::create_dir_all(&dir).unwrap();
+    fs::write(
+        dir.join("padded.svg"),
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="24" height="24" viewBox="0 0 24 24"><path fill="none" d="M0 0h24v24H0z"/><path d="M4 4h16v16H4z"/></svg>"#,
+    )
+    .unwrap();
+
+    let output = generate(
+        "unfilled",
+        r#"[{ "name": "padded", "path": "padded.svg", "raster_sizes": [6] }]"#,
+    );
+
+    // The padding square leaves no trace in the outline, its bounds or masks.
+    assert!(output.contains("data: \"M4 4h16v16H4z\""));
+    assert!(output.contains("bounding_box: Rect { x: 4.0, y: 4.0, width: 16.0, height: 16.0 }"));
+    assert_eq!(
+        included(&output, "ICON_PADDED_OUTLINED_MASK_6"),
+        [
+            [0, 0, 0, 0, 0, 0],
+            [0, 255, 255, 255, 255, 0],
+            [0, 255, 255, 255, 255, 0],
+            [0, 255, 255, 255, 255, 0],
+            [0, 255, 255, 255, 255, 0],
+            [0, 0, 0, 0, 0, 0],
+        ]
+        .concat()
+    );
+}
+
+#[test]
+fn test_unquoted_attributes() {
+    let dir = Path::new(env!("CARGO_TARGET_TMPDIR")).join("codegen");
+
+    fs::create_dir_all(&dir).unwrap();
+    fs::write(
+        dir.join("unquoted.svg"),
+        r#"<svg xmlns=http://www.w3.org/2000/svg width=48px height=48 viewBox=0,0,24,24><path fill=none d="M0 0h24v24H0z"/><path d="M4 4h16v16H4z"/></svg>"#,
+    )
+    .unwrap();
+
+    let output = generate(
+        "unquoted",
+        r#"[{ "name": "unquoted", "path": "unquoted.svg" }]"#,
+    );
+
+    assert!(output.contains("data: \"M4 4h16v16H4z\""));
+    assert!(output.contains(
+        "view_box: Rect { x: 0.0, y: 0.0, width: 24.0, height: 24.0 }, width: 48.0, height: 48.0"
+    ));
+}
+
+#[test]
+fn test_search_paths() {
+    let dir = Path::new(env!("CARGO_TARGET_TMPDIR")).join("codegen");
+
+    for search_path in ["first", "second"] {
+        fs::create_dir_all(dir.join(search_path).join("home")).unwrap();
+        fs::write(
+            dir.join(search_path).join("home/outlined.svg"),
+            r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 24 24"><path d="M4 4h16v16H4z"/></svg>"#,
+        )
+        .unwrap();
+    }
+
+    let (output, stderr) = generate_with_warnings(
+        "search-paths",
+        r#"{ "search_paths": ["first", "second"], "icons": ["home", "check"] }"#,
+    );
+
+    // The first search path wins, and the shipped icons come last.
+    assert!(output.contains(&format!(
+        "include_bytes!({:?})",
+        dir.join("first/home/outlined.svg").display().to_string()
+    )));
+    assert!(!output.contains("second/home"));
+    assert!(output.contains("/icons/check/outlined.svg"));
+    assert!(stderr.contains("replaces the shipped icon of the same name"));
+}
+
+#[test]
+fn test_custom_axes() {
+    let dir = Path::new(env!("CARGO_TARGET_TMPDIR")).join("codegen/axes/home");
+
+    fs::create_dir_all(&dir).unwrap();
+    fs::write(
+        dir.join("outlined-wght300.svg"),
+        r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 24 24"><path d="M4 4h16v16H4z"/></svg>"#,
+    )
+    .unwrap();
+
+    // Variants the shipped icons don't come in add to them, without a warning.
+    let output = generate(
+        "custom-axes",
+        r#"{ "search_paths": ["axes"], "icons": ["home", { "name": "home", "weight": 300 }] }"#,
+    );
+
+    assert!(output.contains("axes/home/outlined-wght300.svg"));
+    assert!(output.contains("/icons/home/outlined.svg"));
+}
+
+#[test]
+fn test_manifest_config() {
+    let dir = project(
+        "manifest-config",
+        &[
+            (
+                "Cargo.toml",
+                r#"
+                [package]
+                name = "consumer"
+
+                [package.metadata.material-icons]
+                config = "assets/icons.json"
+                "#,
+            ),
+            ("assets/icons.json", r#"["alarm"]"#),
+            // Only used when the manifest doesn't say otherwise.
+            ("icons.json", r#"["home"]"#),
+        ],
+    );
+    let output = discover(&dir.join("target"), &dir).unwrap();
+
+    assert!(output.contains("ICON_ALARM_OUTLINED"));
+    assert!(!output.contains("ICON_HOME_OUTLINED"));
+}
+
+#[test]
+fn test_manifest_icons() {
+    let dir = project(
+        "manifest-icons",
+        &[(
+            "Cargo.toml",
+            r#"
+            [package]
+            name = "consumer"
+
+            [package.metadata.material-icons]
+            icons = ["alarm", { name = "check", style = "rounded", filled = true }]
+            "#,
+        )],
+    );
+    let output = discover(&dir.join("target"), &dir).unwrap();
+
+    assert!(output.contains("ICON_ALARM_OUTLINED"));
+    assert!(output.contains("ICON_CHECK_FILLED_ROUNDED"));
+    assert!(!output.contains("ICON_CHECK_OUTLINED"));
+}
+
+#[test]
+fn test_workspace_member() {
+    let dir = project(
+        "workspace-member",
+        &[
+            ("Cargo.toml", "[workspace]\nmembers = [\"crates/*\"]\n"),
+            (
+                "crates/app/Cargo.toml",
+                r#"
+                [package]
+                name = "app"
+
+                [package.metadata.material-icons]
+                icons = ["alarm"]
+                "#,
+            ),
+            ("crates/cli/Cargo.toml", "[package]\nname = \"cli\"\n"),
+        ],
+    );
+    let output = discover(&dir.join("target"), &dir).unwrap();
+
+    assert!(output.contains("ICON_ALARM_OUTLINED"));
+}
+
+#[test]
+fn test_ambiguous_workspace_members() {
+    let dir = project(
+        "ambiguous-workspace-members",
+        &[
+            ("Cargo.toml", "[workspace]\nmembers = [\"crates/*\"]\n"),
+            (
+                "crates/app/Cargo.toml",
+                r#"
+                [package]
+                name = "app"
+
+                [package.metadata.material-icons]
+                icons = ["alarm"]
+                "#,
+            ),
+            ("crates/cli/icons.json", r#"["home"]"#),
+            (
+                "crates/cli/Cargo.toml",
+                r#"
+                [package]
+                name = "cli"
+
+                [package.metadata.material-icons]
+                config = "icons.json"
+                "#,
+            ),
+        ],
+    );
+    let err = discover(&dir.join("target"), &dir).unwrap_err();
+
+    assert!(
+        err.contains("Several members of the workspace at"),
+        "Unexpected error:\n{err}"
+    );
+}
+
+#[test]
+fn test_external_target_dir() {
+    let dir = project(
+        "external-target-dir",
+        &[(
+            "Cargo.toml",
+            r#"
+            [workspace.metadata.material-icons]
+            icons = ["alarm"]
+            "#,
+        )],
+    );
+    // No manifest is above the target directory, so only the directory cargo
+    // is invoked from leads to the config. It's shared by every checkout of the
+    // crate unless named after this one, and cleared for builds of earlier
+    // versions not to be mistaken for this one.
+    let mut checkout = DefaultHasher::new();
+
+    env!("CARGO_MANIFEST_DIR").hash(&mut checkout);
+
+    let target_dir = env::temp_dir().join(format!(
+        "material-icons-external-target-{:016x}",
+        checkout.finish()
+    ));
+
+    if target_dir.exists() {
+        fs::remove_dir_all(&target_dir).unwrap();
+    }
+
+    let output = discover(&target_dir, &dir).unwrap();
+
+    assert!(output.contains("ICON_ALARM_OUTLINED"));
+    assert!(output.starts_with("// Icons from [workspace.metadata.material-icons] icons in"));
+    assert!(output.contains("found above $PWD rather than the target directory"));
+}